@@ -0,0 +1,132 @@
+//! Read-through cache for values that don't change, eg. the identity object
+//! ```no_run
+//! # use canopeners::{Conn, cache::SdoCache, eds};
+//! let mut conn = Conn::new("vcan0").unwrap();
+//! let mut cache = SdoCache::new().with_description(eds::load("device.eds").unwrap());
+//! // only the first read of a const/ro entry goes to the node
+//! let device_type: u32 = cache.read_typed(&mut conn, 5, 0x1000, 0).unwrap();
+//! let device_type: u32 = cache.read_typed(&mut conn, 5, 0x1000, 0).unwrap();
+//! // a boot-up or NMT reset forgets everything cached for the node
+//! if let Ok(msg) = conn.recv() {
+//!     cache.process(&msg);
+//! }
+//! ```
+//! Which entries are cached depends on their access type in the node's description: `const` and `ro`.
+//! Read-only process values (eg. a statusword) do change, read those with `read_uncached`.
+
+use std::collections::BTreeMap;
+
+use crate::enums::AccessType;
+use crate::od::ObjectDictionary;
+use crate::value::{self, SdoValue};
+use crate::{CanOpenError, Conn, GuardStatus, Message, NmtFunction};
+
+#[derive(Clone, Debug, Default)]
+pub struct SdoCache {
+    description: Option<ObjectDictionary>,
+    node_descriptions: BTreeMap<u8, ObjectDictionary>,
+    values: BTreeMap<(u8, u16, u8), Box<[u8]>>,
+}
+
+impl SdoCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Description of the nodes without one of their own
+    pub fn with_description(mut self, description: ObjectDictionary) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn with_node_description(mut self, node_id: u8, description: ObjectDictionary) -> Self {
+        self.node_descriptions.insert(node_id, description);
+        self
+    }
+
+    /// Whether the node's description says the entry is `const` or `ro`
+    pub fn is_cacheable(&self, node_id: u8, index: u16, sub_index: u8) -> bool {
+        self.node_descriptions
+            .get(&node_id)
+            .or(self.description.as_ref())
+            .and_then(|d| d.variable(index, sub_index))
+            .is_some_and(|v| matches!(v.access_type, AccessType::Const | AccessType::ReadOnly))
+    }
+
+    /// The cached value, without going to the node
+    pub fn cached(&self, node_id: u8, index: u16, sub_index: u8) -> Option<&[u8]> {
+        self.values.get(&(node_id, index, sub_index)).map(|v| &**v)
+    }
+
+    /// Like `Conn::sdo_read`, but served from the cache once the value was read
+    pub fn read(
+        &mut self,
+        conn: &mut Conn,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<Box<[u8]>, CanOpenError> {
+        match self.cached(node_id, index, sub_index) {
+            Some(data) => Ok(data.into()),
+            None => self.read_uncached(conn, node_id, index, sub_index),
+        }
+    }
+
+    /// Always reads from the node, updating the cache
+    pub fn read_uncached(
+        &mut self,
+        conn: &mut Conn,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<Box<[u8]>, CanOpenError> {
+        let data = conn.sdo_read(node_id, index, sub_index)?;
+        if self.is_cacheable(node_id, index, sub_index) {
+            self.values
+                .insert((node_id, index, sub_index), data.clone());
+        }
+        Ok(data)
+    }
+
+    /// Like `Conn::sdo_read_typed`, but served from the cache once the value was read
+    pub fn read_typed<T: SdoValue>(
+        &mut self,
+        conn: &mut Conn,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<T, CanOpenError> {
+        let data = self.read(conn, node_id, index, sub_index)?;
+        value::decode_read(index, sub_index, &data)
+    }
+
+    /// Forgets everything cached for `node_id`
+    pub fn invalidate(&mut self, node_id: u8) {
+        self.values.retain(|(node, _, _), _| *node != node_id);
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    /// Invalidates nodes that booted, or that were told to reset (node or communication)
+    pub fn process(&mut self, message: &Message) {
+        match message {
+            Message::Guard(guard) if guard.status() == GuardStatus::Boot => {
+                self.invalidate(guard.node_id())
+            }
+            Message::Nmt(nmt)
+                if matches!(
+                    nmt.function,
+                    NmtFunction::ResetNode | NmtFunction::ResetCommunication
+                ) =>
+            {
+                match nmt.target_node {
+                    0 => self.clear(),
+                    node_id => self.invalidate(node_id),
+                }
+            }
+            _ => {}
+        }
+    }
+}
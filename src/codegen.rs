@@ -0,0 +1,199 @@
+//! Generates typed accessors (see `value::Entry`) from a device description, meant for build scripts:
+//! ```no_run
+//! // build.rs
+//! let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("drive.rs");
+//! canopeners::codegen::generate_file("drive.eds", &out).unwrap();
+//! println!("cargo:rerun-if-changed=drive.eds");
+//! ```
+//! ```ignore
+//! // src/main.rs
+//! #[allow(dead_code)]
+//! mod drive {
+//!     include!(concat!(env!("OUT_DIR"), "/drive.rs"));
+//! }
+//! drive::controlword().write(&mut conn, 5, 0x0F)?;
+//! ```
+//! Every variable gets a constant (`CONTROLWORD`) and a function returning it (`controlword()`).
+//! Sub-indices are named after their object and themselves, eg. `identity_vendor_id()`.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::enums::{AccessType, DataType};
+use crate::od::{ObjectDictionary, Variable};
+use crate::{eds, xdd, CanOpenError};
+
+/// Strict and reserved keywords (up to edition 2024), these can't be used as identifiers
+const KEYWORDS: [&str; 51] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro", "override",
+    "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// "Producer heartbeat time" -> producer_heartbeat_time, "COB-ID" -> cob_id, "NodeID" -> node_id
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            let boundary = c.is_ascii_uppercase()
+                && previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit());
+            if boundary && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else if !out.is_empty() && !out.ends_with('_') {
+            out.push('_');
+        }
+        previous = Some(c);
+    }
+    let mut out = out.trim_end_matches('_').to_owned();
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert_str(0, "obj_");
+    }
+    if KEYWORDS.contains(&out.as_str()) {
+        out.push('_');
+    }
+    out
+}
+
+fn rust_type(data_type: DataType) -> Option<&'static str> {
+    Some(match data_type {
+        DataType::Boolean => "bool",
+        DataType::Integer8 => "i8",
+        DataType::Integer16 => "i16",
+        DataType::Integer24 => "::canopeners::value::I24",
+        DataType::Integer32 => "i32",
+        DataType::Integer40 => "::canopeners::value::I40",
+        DataType::Integer48 => "::canopeners::value::I48",
+        DataType::Integer56 => "::canopeners::value::I56",
+        DataType::Integer64 => "i64",
+        DataType::Unsigned8 => "u8",
+        DataType::Unsigned16 => "u16",
+        DataType::Unsigned24 => "::canopeners::value::U24",
+        DataType::Unsigned32 => "u32",
+        DataType::Unsigned40 => "::canopeners::value::U40",
+        DataType::Unsigned48 => "::canopeners::value::U48",
+        DataType::Unsigned56 => "::canopeners::value::U56",
+        DataType::Unsigned64 => "u64",
+        DataType::Real32 => "f32",
+        DataType::Real64 => "f64",
+        DataType::VisibleString => "String",
+        DataType::OctetString => "Vec<u8>",
+        DataType::UnicodeString => "::canopeners::value::UnicodeString",
+        DataType::TimeOfDay => "::canopeners::TimeOfDay",
+        DataType::TimeDifference => "::canopeners::value::TimeDifference",
+        DataType::Domain => "::canopeners::value::Domain",
+        DataType::Other(_) => return None,
+    })
+}
+
+fn access_marker(access_type: AccessType) -> &'static str {
+    match access_type {
+        AccessType::ReadOnly => "ReadOnly",
+        AccessType::WriteOnly => "WriteOnly",
+        AccessType::ReadWrite | AccessType::ReadWriteRead | AccessType::ReadWriteWrite => {
+            "ReadWrite"
+        }
+        AccessType::Const => "Const",
+    }
+}
+
+fn write_entry(
+    out: &mut String,
+    name: &str,
+    variable: &Variable,
+    rust_type: &str,
+) -> std::fmt::Result {
+    let entry_type = format!(
+        "::canopeners::value::Entry<{rust_type}, ::canopeners::value::{}>",
+        access_marker(variable.access_type)
+    );
+    writeln!(
+        out,
+        "\n/// {} ({:#06x}sub{}), {:?}, {}",
+        variable.name,
+        variable.index,
+        variable.sub_index,
+        variable.data_type,
+        variable.access_type.encode()
+    )?;
+    writeln!(
+        out,
+        "pub const {}: {entry_type} = ::canopeners::value::Entry::new({:#06x}, {});",
+        name.to_ascii_uppercase(),
+        variable.index,
+        variable.sub_index
+    )?;
+    writeln!(
+        out,
+        "\n/// {} ({:#06x}sub{})",
+        variable.name, variable.index, variable.sub_index
+    )?;
+    writeln!(
+        out,
+        "pub const fn {name}() -> {entry_type} {{\n    {}\n}}",
+        name.to_ascii_uppercase()
+    )
+}
+
+/// Generates the accessor module's source code
+pub fn generate(od: &ObjectDictionary) -> String {
+    let mut out = String::new();
+    generate_to(&mut out, od).expect("writing to a String can't fail");
+    out
+}
+
+fn generate_to(out: &mut String, od: &ObjectDictionary) -> std::fmt::Result {
+    writeln!(out, "// Generated by canopeners::codegen, do not edit.")?;
+    if !od.file_info.file_name.is_empty() {
+        writeln!(out, "// Source: {}", od.file_info.file_name)?;
+    }
+    let mut used = BTreeSet::new();
+    for object in od.objects() {
+        let object_name = snake_case(&object.name);
+        for variable in object.sub_objects.values() {
+            let mut name = if object.object_type.has_sub_objects() {
+                format!("{object_name}_{}", snake_case(&variable.name))
+            } else {
+                object_name.clone()
+            };
+            // eg. the same ParameterName on several objects
+            if used.contains(&name) {
+                name = format!("{name}_{:04x}sub{}", variable.index, variable.sub_index);
+            }
+            let Some(rust_type) = rust_type(variable.data_type) else {
+                writeln!(
+                    out,
+                    "\n// {} ({:#06x}sub{}) skipped, no Rust type for {:?}",
+                    variable.name, variable.index, variable.sub_index, variable.data_type
+                )?;
+                continue;
+            };
+            write_entry(out, &name, variable, rust_type)?;
+            used.insert(name);
+        }
+    }
+    Ok(())
+}
+
+/// Reads an EDS (or XDD/XDC, by extension) and writes the generated module to `out`
+pub fn generate_file(
+    description: impl AsRef<Path>,
+    out: impl AsRef<Path>,
+) -> Result<(), CanOpenError> {
+    let description = description.as_ref();
+    let is_xml = description
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("xdd") || e.eq_ignore_ascii_case("xdc"));
+    let od = if is_xml {
+        xdd::load(description)?
+    } else {
+        eds::load(description)?
+    };
+    std::fs::write(out, generate(&od)).map_err(CanOpenError::IOError)
+}
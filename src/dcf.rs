@@ -0,0 +1,196 @@
+//! DCF (device configuration file, CiA 306)
+//! A DCF is an EDS plus the actual values of one configured device (`ParameterValue`)
+//! and where that device lives (`[DeviceComissioning]`), so it's parsed into the same `ObjectDictionary`.
+//! ```no_run
+//! # use canopeners::{Conn, eds, dcf};
+//! let mut conn = Conn::new("vcan0").unwrap();
+//! let eds = eds::load("device.eds").unwrap();
+//! // snapshot node 5 after commissioning...
+//! let (snapshot, _failed) = dcf::read_from_node(&mut conn, 5, &eds).unwrap();
+//! dcf::save(&snapshot, "node5.dcf").unwrap();
+//! // ...and restore it later
+//! let failed = dcf::write_to_node(&mut conn, 5, &dcf::load("node5.dcf").unwrap()).unwrap();
+//! for entry in failed {
+//!     println!("{:#06x}sub{}: {}", entry.index, entry.sub_index, entry.failure);
+//! }
+//! ```
+
+use std::fmt::Display;
+use std::path::Path;
+
+use crate::enums::{AbortCode, DataType};
+use crate::od::{DeviceCommissioning, ObjectDictionary, ValueExpr, Variable};
+use crate::{eds, CanOpenError, Conn};
+
+/// Why an entry couldn't be read or written
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// The node aborted the SDO transfer
+    Aborted(AbortCode),
+    /// The node sent `len` bytes, which doesn't fit the described data type
+    WrongSize { len: usize },
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::Aborted(abort_code) => write!(f, "{abort_code}"),
+            Failure::WrongSize { len } => write!(f, "wrong size, got {len} bytes"),
+        }
+    }
+}
+
+/// An entry the node refused
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FailedEntry {
+    pub index: u16,
+    pub sub_index: u8,
+    pub failure: Failure,
+}
+
+pub fn parse(s: &str) -> Result<ObjectDictionary, CanOpenError> {
+    eds::parse(s)
+}
+
+pub fn load(path: impl AsRef<Path>) -> Result<ObjectDictionary, CanOpenError> {
+    eds::load(path)
+}
+
+pub fn save(dcf: &ObjectDictionary, path: impl AsRef<Path>) -> Result<(), CanOpenError> {
+    eds::save(dcf, path)
+}
+
+fn is_pdo_communication(index: u16) -> bool {
+    matches!(index, 0x1400..=0x15FF | 0x1800..=0x19FF)
+}
+
+fn is_pdo_mapping(index: u16) -> bool {
+    matches!(index, 0x1600..=0x17FF | 0x1A00..=0x1BFF)
+}
+
+/// Entries that make up a device's configuration.
+/// Domains can be huge (eg. firmware), and store/restore parameters (0x1010, 0x1011) are commands, not settings.
+pub(crate) fn is_configuration(variable: &Variable) -> bool {
+    !matches!(variable.data_type, DataType::Domain | DataType::Other(_))
+        && !matches!(variable.index, 0x1010 | 0x1011)
+}
+
+/// SDO aborts become `FailedEntry`s, anything else (eg. the bus going away) is an error
+fn record_abort(
+    result: Result<(), CanOpenError>,
+    failed: &mut Vec<FailedEntry>,
+) -> Result<(), CanOpenError> {
+    match result {
+        Err(CanOpenError::SdoAbortTransfer {
+            index,
+            sub_index,
+            abort_code,
+        }) => {
+            failed.push(FailedEntry {
+                index,
+                sub_index,
+                failure: Failure::Aborted(abort_code),
+            });
+            Ok(())
+        }
+        other => other,
+    }
+}
+
+/// Reads every readable configuration entry described by `eds` from the node.
+/// Returns a DCF with `ParameterValue`s for everything that could be read, plus the entries that couldn't.
+pub fn read_from_node(
+    conn: &mut Conn,
+    node_id: u8,
+    eds: &ObjectDictionary,
+) -> Result<(ObjectDictionary, Vec<FailedEntry>), CanOpenError> {
+    let mut dcf = eds.clone();
+    let mut failed = Vec::new();
+    let entries: Vec<(u16, u8, DataType)> = eds
+        .variables()
+        .filter(|v| v.access_type.is_readable() && is_configuration(v))
+        .map(|v| (v.index, v.sub_index, v.data_type))
+        .collect();
+    for (index, sub_index, data_type) in entries {
+        let result = conn.sdo_read(node_id, index, sub_index).map(|data| {
+            match ValueExpr::from_bytes(data_type, &data) {
+                Ok(value) => {
+                    if let Some(variable) = dcf.variable_mut(index, sub_index) {
+                        variable.parameter_value = Some(value);
+                    }
+                }
+                // the node answered with the wrong size for the type
+                Err(_) => failed.push(FailedEntry {
+                    index,
+                    sub_index,
+                    failure: Failure::WrongSize { len: data.len() },
+                }),
+            }
+        });
+        record_abort(result, &mut failed)?;
+    }
+    dcf.device_commissioning = Some(DeviceCommissioning {
+        node_id,
+        ..eds.device_commissioning.clone().unwrap_or_default()
+    });
+    Ok((dcf, failed))
+}
+
+/// Writes every writable entry with a `ParameterValue` to the node.
+/// Values are all encoded before anything is sent, so a malformed DCF doesn't leave the node half configured.
+///
+/// PDOs can't be reconfigured while valid, so the order is:
+/// 1. invalidate PDO COB-IDs (bit 31)
+/// 2. clear PDO mappings (sub-index 0 = 0)
+/// 3. everything else, by index
+/// 4. PDO mapping counts
+/// 5. PDO COB-IDs, as configured
+///
+/// Returns the entries the node refused.
+pub fn write_to_node(
+    conn: &mut Conn,
+    node_id: u8,
+    dcf: &ObjectDictionary,
+) -> Result<Vec<FailedEntry>, CanOpenError> {
+    let mut invalidate_pdos = Vec::new();
+    let mut clear_mappings = Vec::new();
+    let mut entries = Vec::new();
+    let mut mapping_counts = Vec::new();
+    let mut pdo_cob_ids = Vec::new();
+    for variable in dcf
+        .variables()
+        .filter(|v| v.access_type.is_writable() && is_configuration(v))
+    {
+        let Some(value) = &variable.parameter_value else {
+            continue;
+        };
+        let (index, sub_index) = (variable.index, variable.sub_index);
+        let data = value
+            .to_bytes(variable.data_type, node_id)
+            .map_err(|e| CanOpenError::ParseError(format!("{index:#06x}sub{sub_index}: {e}")))?;
+        if is_pdo_communication(index) && sub_index == 1 && data.len() == 4 {
+            let cob_id = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+            let invalid = cob_id | 0x8000_0000;
+            invalidate_pdos.push((index, sub_index, invalid.to_le_bytes().to_vec()));
+            pdo_cob_ids.push((index, sub_index, data));
+        } else if is_pdo_mapping(index) && sub_index == 0 {
+            clear_mappings.push((index, sub_index, vec![0]));
+            mapping_counts.push((index, sub_index, data));
+        } else {
+            entries.push((index, sub_index, data));
+        }
+    }
+
+    let mut failed = Vec::new();
+    for (index, sub_index, data) in invalidate_pdos
+        .into_iter()
+        .chain(clear_mappings)
+        .chain(entries)
+        .chain(mapping_counts)
+        .chain(pdo_cob_ids)
+    {
+        let result = conn.sdo_write(node_id, index, sub_index, &data);
+        record_abort(result, &mut failed)?;
+    }
+    Ok(failed)
+}
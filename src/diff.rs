@@ -0,0 +1,185 @@
+//! Compares a live node's configuration with a reference
+//! ```no_run
+//! # use canopeners::{Conn, dcf, diff, eds};
+//! let mut conn = Conn::new("vcan0").unwrap();
+//! // node 5 against its commissioning DCF (or an EDS, for the defaults)...
+//! for entry in diff::against_reference(&mut conn, 5, &dcf::load("node5.dcf").unwrap()).unwrap() {
+//!     println!("{entry}");
+//! }
+//! // ...or against node 6, which is the same kind of device
+//! let eds = eds::load("device.eds").unwrap();
+//! let differences = diff::between_nodes(&mut conn, 5, 6, &eds).unwrap();
+//! ```
+//! Only configuration entries are compared, ie. no domains and no store/restore commands.
+
+use std::fmt::Display;
+
+use crate::dcf::is_configuration;
+use crate::enums::{AbortCode, DataType};
+use crate::od::{ObjectDictionary, ValueExpr, Variable};
+use crate::{CanOpenError, Conn};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Difference {
+    /// Both sides have a value, and they differ
+    Changed {
+        expected: ValueExpr,
+        actual: ValueExpr,
+    },
+    /// The node doesn't have the entry (`ObjectNotInDictionary` or `SubIndexDoesNotExist`)
+    Missing,
+    /// The node has the entry, but reading it failed
+    Unreadable(AbortCode),
+    /// The node sent `len` bytes, which doesn't fit the described data type
+    WrongSize { len: usize },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffEntry {
+    pub index: u16,
+    pub sub_index: u8,
+    pub name: String,
+    /// The node the difference was found on
+    pub node_id: u8,
+    pub difference: Difference,
+}
+
+impl Display for DiffEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "node {} {:#06x}sub{} ({}): ",
+            self.node_id, self.index, self.sub_index, self.name
+        )?;
+        match &self.difference {
+            Difference::Changed { expected, actual } => {
+                write!(f, "expected {}, got {}", expected.as_str(), actual.as_str())
+            }
+            Difference::Missing => write!(f, "missing"),
+            Difference::Unreadable(abort_code) => write!(f, "unreadable, {abort_code}"),
+            Difference::WrongSize { len } => write!(f, "wrong size, got {len} bytes"),
+        }
+    }
+}
+
+/// Reads a value, SDO aborts and values of the wrong size are `Err`s in the result
+fn read(
+    conn: &mut Conn,
+    node_id: u8,
+    variable: &Variable,
+) -> Result<Result<ValueExpr, Difference>, CanOpenError> {
+    match conn.sdo_read(node_id, variable.index, variable.sub_index) {
+        Ok(data) => Ok(ValueExpr::from_bytes(variable.data_type, &data)
+            .map_err(|_| Difference::WrongSize { len: data.len() })),
+        Err(CanOpenError::SdoAbortTransfer { abort_code, .. }) => Ok(Err(failure(abort_code))),
+        Err(e) => Err(e),
+    }
+}
+
+fn failure(abort_code: AbortCode) -> Difference {
+    match abort_code {
+        AbortCode::ObjectNotInDictionary | AbortCode::SubIndexDoesNotExist => Difference::Missing,
+        abort_code => Difference::Unreadable(abort_code),
+    }
+}
+
+fn entry(variable: &Variable, node_id: u8, difference: Difference) -> DiffEntry {
+    DiffEntry {
+        index: variable.index,
+        sub_index: variable.sub_index,
+        name: variable.name.clone(),
+        node_id,
+        difference,
+    }
+}
+
+fn compared(reference: &ObjectDictionary) -> Vec<Variable> {
+    reference
+        .variables()
+        .filter(|v| v.access_type.is_readable() && is_configuration(v))
+        .cloned()
+        .collect()
+}
+
+/// Reads every readable configuration entry of `reference` from the node, and compares it with
+/// the `ParameterValue` (DCF) or `DefaultValue` (EDS). Entries without either are only checked for existence.
+pub fn against_reference(
+    conn: &mut Conn,
+    node_id: u8,
+    reference: &ObjectDictionary,
+) -> Result<Vec<DiffEntry>, CanOpenError> {
+    let mut differences = Vec::new();
+    for variable in compared(reference) {
+        // resolves $NODEID and spells the value like values read from the node
+        let expected = variable
+            .value()
+            .map(|v| {
+                let bytes = v.to_bytes(variable.data_type, node_id)?;
+                ValueExpr::from_bytes(variable.data_type, &bytes)
+            })
+            .transpose()?;
+        match read(conn, node_id, &variable)? {
+            Ok(actual) => {
+                if let Some(expected) = expected.filter(|e| *e != actual) {
+                    let difference = Difference::Changed { expected, actual };
+                    differences.push(entry(&variable, node_id, difference));
+                }
+            }
+            Err(difference) => differences.push(entry(&variable, node_id, difference)),
+        }
+    }
+    Ok(differences)
+}
+
+/// Integers described as `$NODEID+...` (eg. COB-IDs) are compared without the node id
+fn same_value(variable: &Variable, a: (&ValueExpr, u8), b: (&ValueExpr, u8)) -> bool {
+    let node_id_relative = variable.value().is_some_and(|v| v.is_node_id_relative());
+    let integer = variable.data_type.size().is_some()
+        && !matches!(variable.data_type, DataType::Real32 | DataType::Real64);
+    if node_id_relative && integer {
+        let relative = |(value, node_id): (&ValueExpr, u8)| {
+            value.to_integer(0).ok().map(|v| v - node_id as i128)
+        };
+        if let (Some(a), Some(b)) = (relative(a), relative(b)) {
+            return a == b;
+        }
+    }
+    a.0 == b.0
+}
+
+/// Compares two nodes described by `description` with each other.
+/// Differing values are reported on `node_b`, with `node_a`'s as the expected value.
+pub fn between_nodes(
+    conn: &mut Conn,
+    node_a: u8,
+    node_b: u8,
+    description: &ObjectDictionary,
+) -> Result<Vec<DiffEntry>, CanOpenError> {
+    let mut differences = Vec::new();
+    for variable in compared(description) {
+        let a = read(conn, node_a, &variable)?;
+        let b = read(conn, node_b, &variable)?;
+        match (a, b) {
+            (Ok(a), Ok(b)) => {
+                if !same_value(&variable, (&a, node_a), (&b, node_b)) {
+                    let difference = Difference::Changed {
+                        expected: a,
+                        actual: b,
+                    };
+                    differences.push(entry(&variable, node_b, difference));
+                }
+            }
+            // both refused the same way, eg. an optional entry neither node has
+            (Err(a), Err(b)) if a == b => {}
+            (a, b) => {
+                if let Err(difference) = a {
+                    differences.push(entry(&variable, node_a, difference));
+                }
+                if let Err(difference) = b {
+                    differences.push(entry(&variable, node_b, difference));
+                }
+            }
+        }
+    }
+    Ok(differences)
+}
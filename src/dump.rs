@@ -0,0 +1,319 @@
+//! Reads everything a node exposes, for diagnostics
+//! ```no_run
+//! # use canopeners::{Conn, eds, dcf, dump};
+//! let mut conn = Conn::new("vcan0").unwrap();
+//! // walk what the EDS describes...
+//! let dump = dump::from_description(&mut conn, 5, &eds::load("device.eds").unwrap()).unwrap();
+//! // ...or probe indices without one
+//! let dump = dump::probe(&mut conn, 5, 0x1000..=0x1FFF).unwrap();
+//! println!("{}", dump.to_json());
+//! dcf::save(&dump.to_dcf(), "node5.dcf").unwrap();
+//! ```
+//! Entries the node refuses are recorded with their `AbortCode`, the dump carries on.
+
+use std::fmt::Write;
+
+use crate::enums::{AbortCode, AccessType, DataType, ObjectType};
+use crate::od::{DeviceCommissioning, Object, ObjectDictionary, ValueExpr, Variable};
+use crate::{CanOpenError, Conn};
+
+/// One SDO upload, with the raw data or why the node refused it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DumpEntry {
+    pub index: u16,
+    pub sub_index: u8,
+    pub result: Result<Vec<u8>, AbortCode>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dump {
+    pub node_id: u8,
+    /// What was read: the EDS, plus any sub-indices it didn't describe.
+    /// When probing, objects are named after their index and typed DOMAIN.
+    pub description: ObjectDictionary,
+    /// In the order they were read
+    pub entries: Vec<DumpEntry>,
+}
+
+/// SDO aborts are data here, anything else (eg. the bus going away) is an error
+fn read(
+    conn: &mut Conn,
+    node_id: u8,
+    index: u16,
+    sub_index: u8,
+) -> Result<Result<Vec<u8>, AbortCode>, CanOpenError> {
+    match conn.sdo_read(node_id, index, sub_index) {
+        Ok(data) => Ok(Ok(data.into())),
+        Err(CanOpenError::SdoAbortTransfer { abort_code, .. }) => Ok(Err(abort_code)),
+        Err(e) => Err(e),
+    }
+}
+
+fn highest_sub_index(index: u16) -> Variable {
+    Variable::new(
+        index,
+        0,
+        "Highest sub-index supported",
+        DataType::Unsigned8,
+        AccessType::Const,
+    )
+}
+
+/// Description for a sub-index the EDS doesn't list, eg. arrays with a device dependent length
+fn undescribed(object: &Object, sub_index: u8) -> Variable {
+    let like = object
+        .sub_objects
+        .values()
+        .rfind(|v| v.sub_index != 0)
+        .filter(|_| object.object_type == ObjectType::Array);
+    match like {
+        Some(like) => Variable {
+            sub_index,
+            name: format!("{} {sub_index}", object.name),
+            default_value: None,
+            parameter_value: None,
+            ..like.clone()
+        },
+        None => Variable::new(
+            object.index,
+            sub_index,
+            format!("Sub-index {sub_index}"),
+            DataType::Domain,
+            AccessType::ReadOnly,
+        ),
+    }
+}
+
+/// Reads every readable entry `description` (eg. an EDS) lists.
+/// Arrays and records are read up to the count in their sub-index 0.
+pub fn from_description(
+    conn: &mut Conn,
+    node_id: u8,
+    description: &ObjectDictionary,
+) -> Result<Dump, CanOpenError> {
+    let mut dump = Dump {
+        node_id,
+        description: description.clone(),
+        entries: Vec::new(),
+    };
+    for object in description.objects() {
+        let index = object.index;
+        if !object.object_type.has_sub_objects() {
+            for variable in object.sub_objects.values() {
+                if variable.access_type.is_readable() {
+                    dump.push(
+                        index,
+                        variable.sub_index,
+                        read(conn, node_id, index, variable.sub_index)?,
+                    );
+                }
+            }
+            continue;
+        }
+
+        let count = read(conn, node_id, index, 0)?;
+        let highest = match &count {
+            Ok(data) => data.first().copied(),
+            Err(_) => None,
+        };
+        dump.push(index, 0, count);
+        let Some(highest) = highest else {
+            continue;
+        };
+        for sub_index in 1..=highest {
+            match object.sub_object(sub_index) {
+                Some(variable) => {
+                    if variable.access_type.is_readable() {
+                        dump.push(index, sub_index, read(conn, node_id, index, sub_index)?);
+                    }
+                }
+                // not described, eg. a gap in a record (0x1800sub4) or a longer array:
+                // only kept if the node actually has it
+                None => {
+                    if let Ok(data) = read(conn, node_id, index, sub_index)? {
+                        dump.describe(undescribed(object, sub_index));
+                        dump.push(index, sub_index, Ok(data));
+                    }
+                }
+            }
+        }
+    }
+    Ok(dump)
+}
+
+/// Reads whatever the node has in `indices`, without a description.
+/// Objects whose sub-index 0 is a single byte and that also have a sub-index 1 are read like records.
+pub fn probe(
+    conn: &mut Conn,
+    node_id: u8,
+    indices: impl IntoIterator<Item = u16>,
+) -> Result<Dump, CanOpenError> {
+    let mut dump = Dump {
+        node_id,
+        description: ObjectDictionary::new(),
+        entries: Vec::new(),
+    };
+    for index in indices {
+        let sub0 = read(conn, node_id, index, 0)?;
+        let name = format!("Object {index:#06x}");
+        let highest = match &sub0 {
+            Err(AbortCode::ObjectNotInDictionary) => continue,
+            Ok(data) if data.len() == 1 => data[0],
+            _ => 0,
+        };
+        let sub1 = match highest {
+            0 => None,
+            _ => match read(conn, node_id, index, 1)? {
+                Err(AbortCode::SubIndexDoesNotExist | AbortCode::ObjectNotInDictionary) => None,
+                sub1 => Some(sub1),
+            },
+        };
+        let Some(sub1) = sub1 else {
+            dump.describe_object(Object::new(index, name.clone(), ObjectType::Var));
+            dump.describe(Variable::new(
+                index,
+                0,
+                name,
+                DataType::Domain,
+                AccessType::ReadOnly,
+            ));
+            dump.push(index, 0, sub0);
+            continue;
+        };
+
+        let object = Object::new(index, name, ObjectType::Record);
+        dump.describe_object(object.clone());
+        dump.describe(highest_sub_index(index));
+        dump.push(index, 0, sub0);
+        dump.describe(undescribed(&object, 1));
+        dump.push(index, 1, sub1);
+        for sub_index in 2..=highest {
+            dump.describe(undescribed(&object, sub_index));
+            dump.push(index, sub_index, read(conn, node_id, index, sub_index)?);
+        }
+    }
+    Ok(dump)
+}
+
+fn escape_json(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+impl Dump {
+    fn push(&mut self, index: u16, sub_index: u8, result: Result<Vec<u8>, AbortCode>) {
+        self.entries.push(DumpEntry {
+            index,
+            sub_index,
+            result,
+        });
+    }
+
+    fn describe_object(&mut self, object: Object) {
+        self.description.insert(object);
+    }
+
+    fn describe(&mut self, variable: Variable) {
+        if let Some(object) = self.description.get_mut(variable.index) {
+            object.insert(variable);
+        }
+    }
+
+    /// Entries the node refused
+    pub fn failed(&self) -> impl Iterator<Item = &DumpEntry> {
+        self.entries.iter().filter(|e| e.result.is_err())
+    }
+
+    /// The description with a `ParameterValue` for everything that was read, save it with `dcf::save`
+    pub fn to_dcf(&self) -> ObjectDictionary {
+        let mut dcf = self.description.clone();
+        for entry in &self.entries {
+            let Ok(data) = &entry.result else {
+                continue;
+            };
+            if let Some(variable) = dcf.variable_mut(entry.index, entry.sub_index) {
+                // values with the wrong size for their type are only in the JSON
+                variable.parameter_value = ValueExpr::from_bytes(variable.data_type, data).ok();
+            }
+        }
+        dcf.device_commissioning = Some(DeviceCommissioning {
+            node_id: self.node_id,
+            ..self
+                .description
+                .device_commissioning
+                .clone()
+                .unwrap_or_default()
+        });
+        dcf
+    }
+
+    /// ```json
+    /// {
+    ///   "node_id": 5,
+    ///   "entries": [
+    ///     {"index": "0x1000", "sub_index": 0, "name": "Device type", "data_type": "Unsigned32", "value": "0x20192", "data": "92010200"},
+    ///     {"index": "0x2000", "sub_index": 0, "name": "Gain", "data_type": "Integer16", "abort_code": 100794368, "error": "object does not exist in the object dictionary"}
+    ///   ]
+    /// }
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out)
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    fn write_json(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out, "{{")?;
+        writeln!(out, "  \"node_id\": {},", self.node_id)?;
+        writeln!(out, "  \"entries\": [")?;
+        for (i, entry) in self.entries.iter().enumerate() {
+            let variable = self.description.variable(entry.index, entry.sub_index);
+            write!(
+                out,
+                "    {{\"index\": \"{:#06x}\", \"sub_index\": {}",
+                entry.index, entry.sub_index
+            )?;
+            if let Some(variable) = variable {
+                write!(
+                    out,
+                    ", \"name\": \"{}\", \"data_type\": \"{:?}\"",
+                    escape_json(&variable.name),
+                    variable.data_type
+                )?;
+            }
+            match &entry.result {
+                Ok(data) => {
+                    if let Some(value) =
+                        variable.and_then(|v| ValueExpr::from_bytes(v.data_type, data).ok())
+                    {
+                        write!(out, ", \"value\": \"{}\"", escape_json(value.as_str()))?;
+                    }
+                    let hex: String = data.iter().map(|b| format!("{b:02X}")).collect();
+                    write!(out, ", \"data\": \"{hex}\"")?;
+                }
+                Err(abort_code) => write!(
+                    out,
+                    ", \"abort_code\": {}, \"error\": \"{}\"",
+                    abort_code.encode(),
+                    escape_json(abort_code.description())
+                )?,
+            }
+            let separator = if i + 1 < self.entries.len() { "," } else { "" };
+            writeln!(out, "}}{separator}")?;
+        }
+        writeln!(out, "  ]")?;
+        write!(out, "}}")
+    }
+}
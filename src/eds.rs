@@ -0,0 +1,522 @@
+//! EDS (electronic data sheet, CiA 306) parser and writer
+//! Turns the INI file every device ships with into an `ObjectDictionary`:
+//! ```no_run
+//! let od = canopeners::eds::load("device.eds").unwrap();
+//! let heartbeat = od.variable(0x1017, 0).unwrap();
+//! println!("{} is {:?}", heartbeat.name, heartbeat.data_type);
+//! ```
+//! Errors point at the offending line.
+//! DCFs use the same format, so this also reads/writes their extra parts (see `dcf`).
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::enums::{AccessType, DataType, ObjectType};
+use crate::od::{
+    parse_integer, DeviceCommissioning, DeviceInfo, FileInfo, Object, ObjectDictionary, ValueExpr,
+    Variable,
+};
+use crate::CanOpenError;
+
+fn error(line: usize, message: impl Into<String>) -> CanOpenError {
+    CanOpenError::EdsError {
+        line,
+        message: message.into(),
+    }
+}
+
+struct Entry<'a> {
+    key: &'a str,
+    value: &'a str,
+    line: usize,
+}
+
+struct Section<'a> {
+    name: &'a str,
+    line: usize,
+    entries: Vec<Entry<'a>>,
+}
+
+impl<'a> Section<'a> {
+    /// Keys are case insensitive
+    fn get(&self, key: &str) -> Option<&Entry<'a>> {
+        self.entries
+            .iter()
+            .find(|e| e.key.eq_ignore_ascii_case(key))
+    }
+
+    /// Missing and empty values are both None
+    fn value(&self, key: &str) -> Option<&'a str> {
+        self.get(key).map(|e| e.value).filter(|v| !v.is_empty())
+    }
+
+    fn string(&self, key: &str) -> String {
+        self.value(key).unwrap_or_default().to_owned()
+    }
+
+    fn missing(&self, key: &str) -> CanOpenError {
+        error(self.line, format!("[{}] is missing {key}", self.name))
+    }
+
+    fn required(&self, key: &str) -> Result<&Entry<'a>, CanOpenError> {
+        self.get(key)
+            .filter(|e| !e.value.is_empty())
+            .ok_or_else(|| self.missing(key))
+    }
+
+    fn integer<T: TryFrom<i128>>(&self, key: &str) -> Result<Option<T>, CanOpenError> {
+        match self.get(key).filter(|e| !e.value.is_empty()) {
+            None => Ok(None),
+            Some(entry) => parse_integer(entry.value)
+                .and_then(|v| T::try_from(v).ok())
+                .map(Some)
+                .ok_or_else(|| {
+                    error(
+                        entry.line,
+                        format!("{} = {:?} is not a valid number", entry.key, entry.value),
+                    )
+                }),
+        }
+    }
+
+    fn flag(&self, key: &str) -> Result<bool, CanOpenError> {
+        Ok(self.integer::<u8>(key)?.unwrap_or(0) != 0)
+    }
+}
+
+fn split_sections(s: &str) -> Result<Vec<Section<'_>>, CanOpenError> {
+    let mut sections: Vec<Section> = Vec::new();
+    for (i, line) in s.lines().enumerate() {
+        let line_no = i + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .ok_or_else(|| error(line_no, "unterminated section header"))?;
+            sections.push(Section {
+                name: name.trim(),
+                line: line_no,
+                entries: Vec::new(),
+            });
+            continue;
+        }
+        let section = sections
+            .last_mut()
+            .ok_or_else(|| error(line_no, "entry before the first section"))?;
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error(line_no, format!("expected key=value, got {line:?}")))?;
+        section.entries.push(Entry {
+            key: key.trim(),
+            value: value.trim(),
+            line: line_no,
+        });
+    }
+    Ok(sections)
+}
+
+/// Object sections are named by their index in hex, eg. `[1018]`, `[1018sub2]`, `[1018Name]`
+fn parse_section_name(name: &str) -> Option<(u16, &str)> {
+    if name.len() < 4 || !name.is_char_boundary(4) {
+        return None;
+    }
+    let (index, rest) = name.split_at(4);
+    if !index.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some((u16::from_str_radix(index, 16).ok()?, rest))
+}
+
+fn parse_file_info(section: &Section) -> FileInfo {
+    FileInfo {
+        file_name: section.string("FileName"),
+        file_version: section.string("FileVersion"),
+        file_revision: section.string("FileRevision"),
+        eds_version: section.string("EDSVersion"),
+        description: section.string("Description"),
+        creation_time: section.string("CreationTime"),
+        creation_date: section.string("CreationDate"),
+        created_by: section.string("CreatedBy"),
+        modification_time: section.string("ModificationTime"),
+        modification_date: section.string("ModificationDate"),
+        modified_by: section.string("ModifiedBy"),
+    }
+}
+
+const BAUD_RATES: [u16; 8] = [10, 20, 50, 125, 250, 500, 800, 1000];
+
+fn parse_device_info(section: &Section) -> Result<DeviceInfo, CanOpenError> {
+    let mut baud_rates = Vec::new();
+    for rate in BAUD_RATES {
+        if section.flag(&format!("BaudRate_{rate}"))? {
+            baud_rates.push(rate);
+        }
+    }
+    Ok(DeviceInfo {
+        vendor_name: section.string("VendorName"),
+        vendor_number: section.integer("VendorNumber")?,
+        product_name: section.string("ProductName"),
+        product_number: section.integer("ProductNumber")?,
+        revision_number: section.integer("RevisionNumber")?,
+        order_code: section.string("OrderCode"),
+        baud_rates,
+        simple_boot_up_master: section.flag("SimpleBootUpMaster")?,
+        simple_boot_up_slave: section.flag("SimpleBootUpSlave")?,
+        granularity: section.integer("Granularity")?.unwrap_or(0),
+        dynamic_channels_supported: section.integer("DynamicChannelsSupported")?.unwrap_or(0),
+        group_messaging: section.flag("GroupMessaging")?,
+        nr_of_rx_pdo: section.integer("NrOfRXPDO")?.unwrap_or(0),
+        nr_of_tx_pdo: section.integer("NrOfTXPDO")?.unwrap_or(0),
+        lss_supported: section.flag("LSS_Supported")?,
+    })
+}
+
+fn parse_device_commissioning(section: &Section) -> Result<DeviceCommissioning, CanOpenError> {
+    Ok(DeviceCommissioning {
+        node_id: section
+            .integer("NodeID")?
+            .ok_or_else(|| section.missing("NodeID"))?,
+        node_name: section.string("NodeName"),
+        baudrate: section.integer("Baudrate")?.unwrap_or(0),
+        net_number: section.integer("NetNumber")?.unwrap_or(0),
+        network_name: section.string("NetworkName"),
+        canopen_manager: section.flag("CANopenManager")?,
+        lss_serial_number: section.integer("LSS_SerialNumber")?,
+    })
+}
+
+/// Type, access, default and limits of a variable.
+/// `defaults` is used for DOMAIN objects, where type and access are optional.
+fn parse_variable(
+    section: &Section,
+    index: u16,
+    sub_index: u8,
+    defaults: Option<(DataType, AccessType)>,
+) -> Result<Variable, CanOpenError> {
+    let name = section.required("ParameterName")?.value;
+    let data_type = match (section.integer::<u16>("DataType")?, defaults) {
+        (Some(data_type), _) => DataType::decode(data_type),
+        (None, Some((data_type, _))) => data_type,
+        (None, None) => return Err(section.missing("DataType")),
+    };
+    let access_type = match (section.get("AccessType"), defaults) {
+        (Some(entry), _) if !entry.value.is_empty() => {
+            AccessType::decode(entry.value).map_err(|e| error(entry.line, e.to_string()))?
+        }
+        (_, Some((_, access_type))) => access_type,
+        _ => return Err(section.missing("AccessType")),
+    };
+    let mut variable = Variable::new(index, sub_index, name, data_type, access_type);
+    variable.default_value = section.value("DefaultValue").map(ValueExpr::new);
+    variable.pdo_mapping = section.flag("PDOMapping")?;
+    variable.low_limit = section.value("LowLimit").map(ValueExpr::new);
+    variable.high_limit = section.value("HighLimit").map(ValueExpr::new);
+    variable.parameter_value = section.value("ParameterValue").map(ValueExpr::new);
+    Ok(variable)
+}
+
+/// `CompactSubObj=n` describes sub-indices 1..=n in the object's own section,
+/// with names and values optionally overridden in `[xxxxName]` and `[xxxxValue]`
+fn expand_compact(
+    object: &mut Object,
+    section: &Section,
+    count: u8,
+    names: Option<&Section>,
+    values: Option<&Section>,
+) -> Result<(), CanOpenError> {
+    let mut entries = Variable::new(
+        object.index,
+        0,
+        "NrOfEntries",
+        DataType::Unsigned8,
+        AccessType::ReadOnly,
+    );
+    entries.default_value = Some(ValueExpr::new(count.to_string()));
+    object.insert(entries);
+    let template = parse_variable(section, object.index, 1, None)?;
+    for sub_index in 1..=count {
+        let key = sub_index.to_string();
+        let mut variable = template.clone();
+        variable.sub_index = sub_index;
+        variable.name = names
+            .and_then(|n| n.value(&key))
+            .map(str::to_owned)
+            .unwrap_or_else(|| format!("{}{sub_index}", object.name));
+        if let Some(value) = values.and_then(|v| v.value(&key)) {
+            variable.default_value = Some(ValueExpr::new(value));
+        }
+        object.insert(variable);
+    }
+    Ok(())
+}
+
+/// Parses an EDS file's contents
+pub fn parse(s: &str) -> Result<ObjectDictionary, CanOpenError> {
+    let sections = split_sections(s.trim_start_matches('\u{feff}'))?;
+    let mut by_name: BTreeMap<String, &Section> = BTreeMap::new();
+    for section in &sections {
+        if by_name
+            .insert(section.name.to_ascii_uppercase(), section)
+            .is_some()
+        {
+            return Err(error(
+                section.line,
+                format!("duplicate section [{}]", section.name),
+            ));
+        }
+    }
+    let find = |name: String| by_name.get(&name.to_ascii_uppercase()).copied();
+
+    let mut od = ObjectDictionary::new();
+    if let Some(section) = find("FileInfo".to_owned()) {
+        od.file_info = parse_file_info(section);
+    }
+    if let Some(section) = find("DeviceInfo".to_owned()) {
+        od.device_info = parse_device_info(section)?;
+    }
+    if let Some(section) = find("DeviceComissioning".to_owned()) {
+        od.device_commissioning = Some(parse_device_commissioning(section)?);
+    }
+
+    let mut object_sections = Vec::new();
+    let mut sub_sections: BTreeMap<u16, Vec<(u8, &Section)>> = BTreeMap::new();
+    for section in &sections {
+        let Some((index, rest)) = parse_section_name(section.name) else {
+            continue;
+        };
+        if rest.is_empty() {
+            object_sections.push((index, section));
+        } else if rest.len() > 3 && rest[..3].eq_ignore_ascii_case("sub") {
+            let sub_index = u8::from_str_radix(&rest[3..], 16)
+                .map_err(|_| error(section.line, format!("bad sub-index in [{}]", section.name)))?;
+            sub_sections
+                .entry(index)
+                .or_default()
+                .push((sub_index, section));
+        }
+    }
+
+    for (index, section) in object_sections {
+        let name = section.required("ParameterName")?.value;
+        let object_type = match section.integer::<u8>("ObjectType")? {
+            None => ObjectType::Var,
+            Some(code) => ObjectType::decode(code)
+                .map_err(|e| error(section.get("ObjectType").unwrap().line, e.to_string()))?,
+        };
+        let mut object = Object::new(index, name, object_type);
+        match object_type {
+            ObjectType::Var | ObjectType::DefType => {
+                object.insert(parse_variable(section, index, 0, None)?);
+            }
+            ObjectType::Null => {}
+            ObjectType::Domain => {
+                let defaults = (DataType::Domain, AccessType::ReadWrite);
+                object.insert(parse_variable(section, index, 0, Some(defaults))?);
+            }
+            _ => match section.integer::<u8>("CompactSubObj")? {
+                Some(count) if count > 0 => {
+                    let names = find(format!("{index:04X}Name"));
+                    let values = find(format!("{index:04X}Value"));
+                    expand_compact(&mut object, section, count, names, values)?;
+                }
+                _ => {
+                    for (sub_index, sub_section) in sub_sections.remove(&index).unwrap_or_default()
+                    {
+                        object.insert(parse_variable(sub_section, index, sub_index, None)?);
+                    }
+                }
+            },
+        }
+        od.insert(object);
+    }
+
+    // the object lists must only name objects that are described
+    for list in ["MandatoryObjects", "OptionalObjects", "ManufacturerObjects"] {
+        let Some(section) = find(list.to_owned()) else {
+            continue;
+        };
+        for entry in &section.entries {
+            if entry.key.eq_ignore_ascii_case("SupportedObjects") {
+                continue;
+            }
+            let index = parse_integer(entry.value)
+                .and_then(|i| u16::try_from(i).ok())
+                .ok_or_else(|| error(entry.line, format!("{:?} is not an index", entry.value)))?;
+            if od.get(index).is_none() {
+                return Err(error(
+                    entry.line,
+                    format!("[{list}] lists {index:#06x}, which has no section"),
+                ));
+            }
+        }
+    }
+    Ok(od)
+}
+
+/// Reads and parses an EDS file
+pub fn load(path: impl AsRef<Path>) -> Result<ObjectDictionary, CanOpenError> {
+    // plenty of EDS files out there are latin-1 rather than utf-8
+    let bytes = std::fs::read(path).map_err(CanOpenError::IOError)?;
+    parse(&String::from_utf8_lossy(&bytes))
+}
+
+fn write_variable(out: &mut String, variable: &Variable) -> std::fmt::Result {
+    writeln!(out, "DataType={:#06X}", variable.data_type.encode())?;
+    writeln!(out, "AccessType={}", variable.access_type.encode())?;
+    for (key, value) in [
+        ("DefaultValue", &variable.default_value),
+        ("LowLimit", &variable.low_limit),
+        ("HighLimit", &variable.high_limit),
+        ("ParameterValue", &variable.parameter_value),
+    ] {
+        if let Some(value) = value {
+            writeln!(out, "{key}={}", value.as_str())?;
+        }
+    }
+    writeln!(out, "PDOMapping={}", variable.pdo_mapping as u8)
+}
+
+/// Mandatory objects per CiA 301, manufacturer segment 0x2000..=0x5FFF, everything else is optional
+fn object_list(index: u16) -> usize {
+    match index {
+        0x1000 | 0x1001 | 0x1018 => 0,
+        0x2000..=0x5FFF => 2,
+        _ => 1,
+    }
+}
+
+/// Formats `od` as an EDS, or a DCF if it has `ParameterValue`s / `[DeviceComissioning]`.
+/// Compact sub-objects are written out in full.
+pub fn write(od: &ObjectDictionary) -> String {
+    let mut out = String::new();
+    write_to(&mut out, od).expect("writing to a String can't fail");
+    out
+}
+
+fn write_to(out: &mut String, od: &ObjectDictionary) -> std::fmt::Result {
+    let info = &od.file_info;
+    // eg. when converting from XDD, which has no EDS version
+    let eds_version = match info.eds_version.as_str() {
+        "" => "4.0",
+        version => version,
+    };
+    out.push_str("[FileInfo]\n");
+    for (key, value) in [
+        ("FileName", info.file_name.as_str()),
+        ("FileVersion", info.file_version.as_str()),
+        ("FileRevision", info.file_revision.as_str()),
+        ("EDSVersion", eds_version),
+        ("Description", info.description.as_str()),
+        ("CreationTime", info.creation_time.as_str()),
+        ("CreationDate", info.creation_date.as_str()),
+        ("CreatedBy", info.created_by.as_str()),
+        ("ModificationTime", info.modification_time.as_str()),
+        ("ModificationDate", info.modification_date.as_str()),
+        ("ModifiedBy", info.modified_by.as_str()),
+    ] {
+        writeln!(out, "{key}={value}")?;
+    }
+
+    let device = &od.device_info;
+    out.push_str("\n[DeviceInfo]\n");
+    writeln!(out, "VendorName={}", device.vendor_name)?;
+    for (key, value) in [
+        ("VendorNumber", device.vendor_number),
+        ("ProductNumber", device.product_number),
+        ("RevisionNumber", device.revision_number),
+    ] {
+        if let Some(value) = value {
+            writeln!(out, "{key}={value:#X}")?;
+        }
+    }
+    writeln!(out, "ProductName={}", device.product_name)?;
+    writeln!(out, "OrderCode={}", device.order_code)?;
+    for rate in BAUD_RATES {
+        writeln!(
+            out,
+            "BaudRate_{rate}={}",
+            device.baud_rates.contains(&rate) as u8
+        )?;
+    }
+    writeln!(
+        out,
+        "SimpleBootUpMaster={}",
+        device.simple_boot_up_master as u8
+    )?;
+    writeln!(
+        out,
+        "SimpleBootUpSlave={}",
+        device.simple_boot_up_slave as u8
+    )?;
+    writeln!(out, "Granularity={}", device.granularity)?;
+    writeln!(
+        out,
+        "DynamicChannelsSupported={}",
+        device.dynamic_channels_supported
+    )?;
+    writeln!(out, "GroupMessaging={}", device.group_messaging as u8)?;
+    writeln!(out, "NrOfRXPDO={}", device.nr_of_rx_pdo)?;
+    writeln!(out, "NrOfTXPDO={}", device.nr_of_tx_pdo)?;
+    writeln!(out, "LSS_Supported={}", device.lss_supported as u8)?;
+
+    if let Some(commissioning) = &od.device_commissioning {
+        out.push_str("\n[DeviceComissioning]\n");
+        writeln!(out, "NodeID={:#X}", commissioning.node_id)?;
+        writeln!(out, "NodeName={}", commissioning.node_name)?;
+        writeln!(out, "Baudrate={}", commissioning.baudrate)?;
+        writeln!(out, "NetNumber={}", commissioning.net_number)?;
+        writeln!(out, "NetworkName={}", commissioning.network_name)?;
+        writeln!(
+            out,
+            "CANopenManager={}",
+            commissioning.canopen_manager as u8
+        )?;
+        if let Some(serial) = commissioning.lss_serial_number {
+            writeln!(out, "LSS_SerialNumber={serial:#X}")?;
+        }
+    }
+
+    for (list_index, list) in ["MandatoryObjects", "OptionalObjects", "ManufacturerObjects"]
+        .iter()
+        .enumerate()
+    {
+        let indices: Vec<u16> = od
+            .objects()
+            .map(|o| o.index)
+            .filter(|i| object_list(*i) == list_index)
+            .collect();
+        writeln!(out, "\n[{list}]\nSupportedObjects={}", indices.len())?;
+        for (n, index) in indices.iter().enumerate() {
+            writeln!(out, "{}={index:#06X}", n + 1)?;
+        }
+    }
+
+    for object in od.objects() {
+        writeln!(out, "\n[{:04X}]", object.index)?;
+        writeln!(out, "ParameterName={}", object.name)?;
+        writeln!(out, "ObjectType={:#X}", object.object_type as u8)?;
+        if !object.object_type.has_sub_objects() {
+            if let Some(variable) = object.sub_object(0) {
+                write_variable(out, variable)?;
+            }
+            continue;
+        }
+        writeln!(out, "SubNumber={}", object.sub_objects.len())?;
+        for variable in object.sub_objects.values() {
+            writeln!(out, "\n[{:04X}sub{:X}]", object.index, variable.sub_index)?;
+            writeln!(out, "ParameterName={}", variable.name)?;
+            writeln!(out, "ObjectType=0x7")?;
+            write_variable(out, variable)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes `od` to an EDS (or DCF) file
+pub fn save(od: &ObjectDictionary, path: impl AsRef<Path>) -> Result<(), CanOpenError> {
+    std::fs::write(path, write(od)).map_err(CanOpenError::IOError)
+}
@@ -0,0 +1,148 @@
+//! Emergency (EMCY) consumer
+//! Nodes broadcast an EMCY frame whenever an internal error occurs, and an
+//! "error reset" EMCY (error code 0x0000) once the error condition is gone.
+//! `EmergencyConsumer` does the bookkeeping on the master side: it tracks which errors
+//! are currently active on each node and keeps a timestamped history.
+//!
+//! Feed it everything you receive:
+//! ```no_run
+//! # use canopeners::{Conn, emcy::EmergencyConsumer, enums::EmergencyErrorClass};
+//! let conn = Conn::new("vcan0").unwrap();
+//! let mut emcy = EmergencyConsumer::new();
+//! emcy.on_error_class(EmergencyErrorClass::Temperature, |record| {
+//!     println!("node {} is overheating", record.emergency.node_id());
+//! });
+//! loop {
+//!     if let Ok(msg) = conn.recv() {
+//!         emcy.process(&msg);
+//!     }
+//! }
+//! ```
+
+use std::collections::{BTreeMap, VecDeque};
+use std::time::SystemTime;
+
+use crate::enums::{EmergencyErrorClass, EmergencyErrorCode};
+use crate::{Emergency, Message};
+
+/// How many EMCY messages `EmergencyConsumer::new()` remembers
+pub const DEFAULT_HISTORY_CAPACITY: usize = 1024;
+
+/// A received EMCY message, along with the time it was processed
+#[derive(Clone, Debug)]
+pub struct EmergencyRecord {
+    pub timestamp: SystemTime,
+    pub emergency: Emergency,
+}
+
+type Callback = Box<dyn FnMut(&EmergencyRecord) + Send>;
+
+pub struct EmergencyConsumer {
+    // node id -> errors currently reported by that node, oldest first
+    active: BTreeMap<u8, Vec<EmergencyRecord>>,
+    history: VecDeque<EmergencyRecord>,
+    history_capacity: usize,
+    callbacks: Vec<(EmergencyErrorClass, Callback)>,
+}
+
+impl Default for EmergencyConsumer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for EmergencyConsumer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmergencyConsumer")
+            .field("active", &self.active)
+            .field("history", &self.history)
+            .field("history_capacity", &self.history_capacity)
+            .field("callbacks", &self.callbacks.len())
+            .finish()
+    }
+}
+
+impl EmergencyConsumer {
+    pub fn new() -> Self {
+        Self::with_history_capacity(DEFAULT_HISTORY_CAPACITY)
+    }
+
+    /// Once `capacity` messages are recorded, the oldest ones get dropped
+    pub fn with_history_capacity(capacity: usize) -> Self {
+        Self {
+            active: BTreeMap::new(),
+            history: VecDeque::with_capacity(capacity),
+            history_capacity: capacity,
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Call `callback` for every EMCY whose error code falls into `class`.
+    /// Register for `EmergencyErrorClass::ErrorReset` to get notified when a node clears its errors.
+    pub fn on_error_class<F>(&mut self, class: EmergencyErrorClass, callback: F)
+    where
+        F: FnMut(&EmergencyRecord) + Send + 'static,
+    {
+        self.callbacks.push((class, Box::new(callback)));
+    }
+
+    /// Handle any incoming message, non-EMCY messages are ignored
+    pub fn process(&mut self, message: &Message) {
+        if let Message::Emergency(emergency) = message {
+            self.process_emergency(emergency.clone(), SystemTime::now());
+        }
+    }
+
+    /// Handle a single EMCY, `timestamp` is recorded as its arrival time
+    pub fn process_emergency(&mut self, emergency: Emergency, timestamp: SystemTime) {
+        let record = EmergencyRecord {
+            timestamp,
+            emergency,
+        };
+        let node_id = record.emergency.node_id;
+        if record.emergency.error_code == EmergencyErrorCode::ErrorResetOrNoError {
+            // an error reset means the node has no errors left to report
+            self.active.remove(&node_id);
+        } else {
+            let active = self.active.entry(node_id).or_default();
+            // the same error reported again replaces the older record
+            active.retain(|r| r.emergency.error_code_raw != record.emergency.error_code_raw);
+            active.push(record.clone());
+        }
+
+        let class = record.emergency.error_code.class();
+        for (_, callback) in self.callbacks.iter_mut().filter(|(c, _)| *c == class) {
+            callback(&record);
+        }
+
+        if self.history_capacity > 0 {
+            if self.history.len() == self.history_capacity {
+                self.history.pop_front();
+            }
+            self.history.push_back(record);
+        }
+    }
+
+    /// Errors currently active on `node_id`, oldest first
+    pub fn active_errors(&self, node_id: u8) -> &[EmergencyRecord] {
+        self.active.get(&node_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Ids of all nodes with at least one active error
+    pub fn nodes_in_error(&self) -> impl Iterator<Item = u8> + '_ {
+        self.active.keys().copied()
+    }
+
+    pub fn has_active_errors(&self, node_id: u8) -> bool {
+        self.active.contains_key(&node_id)
+    }
+
+    /// Every EMCY processed so far (up to the history capacity), oldest first
+    pub fn history(&self) -> impl Iterator<Item = &EmergencyRecord> {
+        self.history.iter()
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
@@ -1,6 +1,6 @@
 use crate::CanOpenError;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum EmergencyErrorCode {
     ErrorResetOrNoError,
     GenericError,
@@ -37,59 +37,56 @@ pub enum EmergencyErrorCode {
     ExternalError,
     AdditionalFunctions,
     DeviceSpecific,
+    /// Not assigned by CiA 301 (0x0100-0x0FFF, 0xA000-0xEFFF, 0xF100-0xFEFF)
+    Reserved,
 }
 
 impl EmergencyErrorCode {
-    pub fn decode(code: u16) -> Result<Self, CanOpenError> {
+    /// Classifies a raw error code by its upper byte (CiA 301 table 21). Codes within a range
+    /// collapse into one variant, keep the raw code around if you need the exact value
+    /// (see `Emergency::error_code_raw`). Codes in unassigned ranges are `Reserved`.
+    pub fn decode(code: u16) -> Self {
         match code {
-            0x8110 => Some(Self::CommunicationCanOverrun),
-            0x8120 => Some(Self::CommunicationErrorPassiveMode),
-            0x8130 => Some(Self::CommunicationLifeGuardError),
-            0x8140 => Some(Self::CommunicationRecoveredBusOff),
-            0x8150 => Some(Self::CommunicationCanIdCollision),
-            0x8210 => Some(Self::ProtocolErrorPdoLength),
-            0x8220 => Some(Self::ProtocolErrorPdoLengthExceeded),
-            0x8230 => Some(Self::ProtocolErrorDamMpdo),
-            0x8240 => Some(Self::ProtocolErrorUnexpectedSyncLength),
-            0x8250 => Some(Self::ProtocolErrorRpdoTimeout),
-            0x2100..=0x21FF => Some(Self::CurrentInputSide),
-            0x2200..=0x22FF => Some(Self::CurrentInsideDevice),
-            0x2300..=0x23FF => Some(Self::CurrentOutputSide),
-            0x3100..=0x31FF => Some(Self::MainsVoltage),
-            0x3200..=0x32FF => Some(Self::VoltageInsideDevice),
-            0x3300..=0x33FF => Some(Self::OutputVoltage),
-            0x4100..=0x41FF => Some(Self::AmbientTemperature),
-            0x4200..=0x42FF => Some(Self::DeviceTemperature),
-            0x6100..=0x61FF => Some(Self::InternalSoftware),
-            0x6200..=0x62FF => Some(Self::UserSoftware),
-            0x6300..=0x63FF => Some(Self::DataSet),
-            0x8111..=0x811F => Some(Self::Communication),
-            0x8121..=0x812F => Some(Self::Communication),
-            0x8131..=0x813F => Some(Self::Communication),
-            0x8141..=0x814F => Some(Self::Communication),
-            0x8151..=0x81FF => Some(Self::Communication),
-            0x8211..=0x821F => Some(Self::ProtocolError),
-            0x8221..=0x822F => Some(Self::ProtocolError),
-            0x8231..=0x823F => Some(Self::ProtocolError),
-            0x8241..=0x824F => Some(Self::ProtocolError),
-            0x8251..=0x82FF => Some(Self::ProtocolError),
-            0x2000..=0x20FF => Some(Self::Current),
-            0x3000..=0x30FF => Some(Self::Voltage),
-            0x4000..=0x40FF => Some(Self::Temperature),
-            0x5000..=0x50FF => Some(Self::DeviceHardware),
-            0x6000..=0x60FF => Some(Self::DeviceSoftware),
-            0x7000..=0x70FF => Some(Self::AdditionalModules),
-            0x8000..=0x80FF => Some(Self::Monitoring),
-            0x8200..=0x820F => Some(Self::ProtocolError),
-            0x9000..=0x90FF => Some(Self::ExternalError),
-            0xF000..=0xF0FF => Some(Self::AdditionalFunctions),
-            0xFF00..=0xFFFF => Some(Self::DeviceSpecific),
-            0x0000..=0x00FF => Some(Self::ErrorResetOrNoError),
-            0x1000..=0x10FF => Some(Self::GenericError),
-            _ => None,
+            0x8110 => Self::CommunicationCanOverrun,
+            0x8120 => Self::CommunicationErrorPassiveMode,
+            0x8130 => Self::CommunicationLifeGuardError,
+            0x8140 => Self::CommunicationRecoveredBusOff,
+            0x8150 => Self::CommunicationCanIdCollision,
+            0x8210 => Self::ProtocolErrorPdoLength,
+            0x8220 => Self::ProtocolErrorPdoLengthExceeded,
+            0x8230 => Self::ProtocolErrorDamMpdo,
+            0x8240 => Self::ProtocolErrorUnexpectedSyncLength,
+            0x8250 => Self::ProtocolErrorRpdoTimeout,
+            0x0000..=0x00FF => Self::ErrorResetOrNoError,
+            0x1000..=0x1FFF => Self::GenericError,
+            0x2100..=0x21FF => Self::CurrentInputSide,
+            0x2200..=0x22FF => Self::CurrentInsideDevice,
+            0x2300..=0x23FF => Self::CurrentOutputSide,
+            0x2000..=0x2FFF => Self::Current,
+            0x3100..=0x31FF => Self::MainsVoltage,
+            0x3200..=0x32FF => Self::VoltageInsideDevice,
+            0x3300..=0x33FF => Self::OutputVoltage,
+            0x3000..=0x3FFF => Self::Voltage,
+            0x4100..=0x41FF => Self::AmbientTemperature,
+            0x4200..=0x42FF => Self::DeviceTemperature,
+            0x4000..=0x4FFF => Self::Temperature,
+            0x5000..=0x5FFF => Self::DeviceHardware,
+            0x6100..=0x61FF => Self::InternalSoftware,
+            0x6200..=0x62FF => Self::UserSoftware,
+            0x6300..=0x63FF => Self::DataSet,
+            0x6000..=0x6FFF => Self::DeviceSoftware,
+            0x7000..=0x7FFF => Self::AdditionalModules,
+            0x8100..=0x81FF => Self::Communication,
+            0x8200..=0x82FF => Self::ProtocolError,
+            0x8000..=0x8FFF => Self::Monitoring,
+            0x9000..=0x9FFF => Self::ExternalError,
+            0xF000..=0xF0FF => Self::AdditionalFunctions,
+            0xFF00..=0xFFFF => Self::DeviceSpecific,
+            0x0100..=0x0FFF | 0xA000..=0xEFFF | 0xF100..=0xFEFF => Self::Reserved,
         }
-        .ok_or_else(|| CanOpenError::ParseError(format!("bad error code: {}", code)))
     }
+
+    /// Base code of the category, eg. 0xFF00 for `DeviceSpecific`
     pub fn encode(&self) -> u16 {
         match self {
             Self::ErrorResetOrNoError => 0x0000,
@@ -127,11 +124,73 @@ impl EmergencyErrorCode {
             Self::ExternalError => 0x9000,
             Self::AdditionalFunctions => 0xF000,
             Self::DeviceSpecific => 0xFF00,
+            Self::Reserved => 0xA000,
         }
     }
+
+    /// Coarse error class (upper byte of the code in CiA 301 table 21), handy for filtering
+    pub fn class(&self) -> EmergencyErrorClass {
+        use EmergencyErrorClass as C;
+        match self {
+            Self::ErrorResetOrNoError => C::ErrorReset,
+            Self::GenericError => C::Generic,
+            Self::Current
+            | Self::CurrentInputSide
+            | Self::CurrentInsideDevice
+            | Self::CurrentOutputSide => C::Current,
+            Self::Voltage
+            | Self::MainsVoltage
+            | Self::VoltageInsideDevice
+            | Self::OutputVoltage => C::Voltage,
+            Self::Temperature | Self::AmbientTemperature | Self::DeviceTemperature => {
+                C::Temperature
+            }
+            Self::DeviceHardware => C::DeviceHardware,
+            Self::DeviceSoftware | Self::InternalSoftware | Self::UserSoftware | Self::DataSet => {
+                C::DeviceSoftware
+            }
+            Self::AdditionalModules => C::AdditionalModules,
+            Self::Monitoring => C::Monitoring,
+            Self::Communication
+            | Self::CommunicationCanOverrun
+            | Self::CommunicationErrorPassiveMode
+            | Self::CommunicationLifeGuardError
+            | Self::CommunicationRecoveredBusOff
+            | Self::CommunicationCanIdCollision => C::Communication,
+            Self::ProtocolError
+            | Self::ProtocolErrorPdoLength
+            | Self::ProtocolErrorPdoLengthExceeded
+            | Self::ProtocolErrorDamMpdo
+            | Self::ProtocolErrorUnexpectedSyncLength
+            | Self::ProtocolErrorRpdoTimeout => C::Protocol,
+            Self::ExternalError => C::External,
+            Self::AdditionalFunctions => C::AdditionalFunctions,
+            Self::DeviceSpecific => C::DeviceSpecific,
+            Self::Reserved => C::Reserved,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EmergencyErrorClass {
+    ErrorReset,
+    Generic,
+    Current,
+    Voltage,
+    Temperature,
+    DeviceHardware,
+    DeviceSoftware,
+    AdditionalModules,
+    Monitoring,
+    Communication,
+    Protocol,
+    External,
+    AdditionalFunctions,
+    DeviceSpecific,
+    Reserved,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum EmergencyErrorRegister {
     GenericError,
     Current,
@@ -191,7 +250,8 @@ impl EmergencyErrorRegister {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// Compared by code, so `Other(0x0602_0000)` equals `ObjectNotInDictionary`
+#[derive(Clone, Copy, Debug)]
 pub enum AbortCode {
     ToggleBitNotAlternated,
     SdoProtocolTimedOut,
@@ -224,43 +284,66 @@ pub enum AbortCode {
     DeviceStatePreventsDataTransfer,
     ObjectDictionaryGenerationFailed,
     NoDataAvailable,
+    /// Not in the CiA 301 table, usually manufacturer specific.
+    /// `decode` (and `From<u32>`) never return it for a code that has a variant of its own.
+    Other(u32),
+}
+
+impl PartialEq for AbortCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.encode() == other.encode()
+    }
+}
+
+impl Eq for AbortCode {}
+
+impl From<u32> for AbortCode {
+    fn from(code: u32) -> Self {
+        Self::decode(code)
+    }
+}
+
+impl From<AbortCode> for u32 {
+    fn from(abort_code: AbortCode) -> Self {
+        abort_code.encode()
+    }
 }
 
 impl AbortCode {
-    pub fn decode(code: u32) -> Option<Self> {
+    pub fn decode(code: u32) -> Self {
         match code {
-            0x0503_0000 => Some(Self::ToggleBitNotAlternated),
-            0x0504_0000 => Some(Self::SdoProtocolTimedOut),
-            0x0504_0001 => Some(Self::InvalidClientServerCommandSpecifier),
-            0x0504_0002 => Some(Self::InvalidBlockSize),
-            0x0504_0003 => Some(Self::InvalidSequenceNumber),
-            0x0504_0004 => Some(Self::CrcError),
-            0x0504_0005 => Some(Self::OutOfMemory),
-            0x0601_0000 => Some(Self::UnsupportedAccessToObject),
-            0x0601_0001 => Some(Self::AttemptToReadWriteOnlyObject),
-            0x0601_0002 => Some(Self::AttemptToWriteReadOnlyObject),
-            0x0602_0000 => Some(Self::ObjectNotInDictionary),
-            0x0604_0041 => Some(Self::ObjectCannotBeMappedToPdo),
-            0x0604_0042 => Some(Self::ExceedPdoLength),
-            0x0604_0043 => Some(Self::GeneralParameterIncompatibility),
-            0x0604_0047 => Some(Self::GeneralInternalIncompatibility),
-            0x0606_0000 => Some(Self::HardwareError),
-            0x0607_0010 => Some(Self::DataTypeMismatchLengthMismatch),
-            0x0607_0012 => Some(Self::DataTypeMismatchLengthTooHigh),
-            0x0607_0013 => Some(Self::DataTypeMismatchLengthTooLow),
-            0x0609_0011 => Some(Self::SubIndexDoesNotExist),
-            0x0609_0030 => Some(Self::InvalidValueForParameter),
-            0x0609_0031 => Some(Self::ValueTooHigh),
-            0x0609_0032 => Some(Self::ValueTooLow),
-            0x0609_0036 => Some(Self::MaxLessThanMin),
-            0x060A_0023 => Some(Self::ResourceNotAvailable),
-            0x0800_0000 => Some(Self::GeneralError),
-            0x0800_0020 => Some(Self::DataTransferOrStorageFailed),
-            0x0800_0021 => Some(Self::LocalControlPreventsDataTransfer),
-            0x0800_0022 => Some(Self::DeviceStatePreventsDataTransfer),
-            0x0800_0023 => Some(Self::ObjectDictionaryGenerationFailed),
-            0x0800_0024 => Some(Self::NoDataAvailable),
-            _ => None,
+            0x0503_0000 => Self::ToggleBitNotAlternated,
+            0x0504_0000 => Self::SdoProtocolTimedOut,
+            0x0504_0001 => Self::InvalidClientServerCommandSpecifier,
+            0x0504_0002 => Self::InvalidBlockSize,
+            0x0504_0003 => Self::InvalidSequenceNumber,
+            0x0504_0004 => Self::CrcError,
+            0x0504_0005 => Self::OutOfMemory,
+            0x0601_0000 => Self::UnsupportedAccessToObject,
+            0x0601_0001 => Self::AttemptToReadWriteOnlyObject,
+            0x0601_0002 => Self::AttemptToWriteReadOnlyObject,
+            0x0602_0000 => Self::ObjectNotInDictionary,
+            0x0604_0041 => Self::ObjectCannotBeMappedToPdo,
+            0x0604_0042 => Self::ExceedPdoLength,
+            0x0604_0043 => Self::GeneralParameterIncompatibility,
+            0x0604_0047 => Self::GeneralInternalIncompatibility,
+            0x0606_0000 => Self::HardwareError,
+            0x0607_0010 => Self::DataTypeMismatchLengthMismatch,
+            0x0607_0012 => Self::DataTypeMismatchLengthTooHigh,
+            0x0607_0013 => Self::DataTypeMismatchLengthTooLow,
+            0x0609_0011 => Self::SubIndexDoesNotExist,
+            0x0609_0030 => Self::InvalidValueForParameter,
+            0x0609_0031 => Self::ValueTooHigh,
+            0x0609_0032 => Self::ValueTooLow,
+            0x0609_0036 => Self::MaxLessThanMin,
+            0x060A_0023 => Self::ResourceNotAvailable,
+            0x0800_0000 => Self::GeneralError,
+            0x0800_0020 => Self::DataTransferOrStorageFailed,
+            0x0800_0021 => Self::LocalControlPreventsDataTransfer,
+            0x0800_0022 => Self::DeviceStatePreventsDataTransfer,
+            0x0800_0023 => Self::ObjectDictionaryGenerationFailed,
+            0x0800_0024 => Self::NoDataAvailable,
+            other => Self::Other(other),
         }
     }
 
@@ -297,7 +380,276 @@ impl AbortCode {
             Self::DeviceStatePreventsDataTransfer => 0x0800_0022,
             Self::ObjectDictionaryGenerationFailed => 0x0800_0023,
             Self::NoDataAvailable => 0x0800_0024,
+            Self::Other(code) => *code,
+        }
+    }
+
+    /// Meaning of the code, as worded in CiA 301 table 22
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::ToggleBitNotAlternated => "toggle bit not alternated",
+            Self::SdoProtocolTimedOut => "SDO protocol timed out",
+            Self::InvalidClientServerCommandSpecifier => {
+                "client/server command specifier not valid or unknown"
+            }
+            Self::InvalidBlockSize => "invalid block size",
+            Self::InvalidSequenceNumber => "invalid sequence number",
+            Self::CrcError => "CRC error",
+            Self::OutOfMemory => "out of memory",
+            Self::UnsupportedAccessToObject => "unsupported access to an object",
+            Self::AttemptToReadWriteOnlyObject => "attempt to read a write only object",
+            Self::AttemptToWriteReadOnlyObject => "attempt to write a read only object",
+            Self::ObjectNotInDictionary => "object does not exist in the object dictionary",
+            Self::ObjectCannotBeMappedToPdo => "object cannot be mapped to the PDO",
+            Self::ExceedPdoLength => {
+                "the number and length of the objects to be mapped would exceed PDO length"
+            }
+            Self::GeneralParameterIncompatibility => "general parameter incompatibility reason",
+            Self::GeneralInternalIncompatibility => "general internal incompatibility in the device",
+            Self::HardwareError => "access failed due to a hardware error",
+            Self::DataTypeMismatchLengthMismatch => {
+                "data type does not match, length of service parameter does not match"
+            }
+            Self::DataTypeMismatchLengthTooHigh => {
+                "data type does not match, length of service parameter too high"
+            }
+            Self::DataTypeMismatchLengthTooLow => {
+                "data type does not match, length of service parameter too low"
+            }
+            Self::SubIndexDoesNotExist => "sub-index does not exist",
+            Self::InvalidValueForParameter => "invalid value for parameter",
+            Self::ValueTooHigh => "value of parameter written too high",
+            Self::ValueTooLow => "value of parameter written too low",
+            Self::MaxLessThanMin => "maximum value is less than minimum value",
+            Self::ResourceNotAvailable => "resource not available: SDO connection",
+            Self::GeneralError => "general error",
+            Self::DataTransferOrStorageFailed => {
+                "data cannot be transferred or stored to the application"
+            }
+            Self::LocalControlPreventsDataTransfer => {
+                "data cannot be transferred or stored to the application because of local control"
+            }
+            Self::DeviceStatePreventsDataTransfer => {
+                "data cannot be transferred or stored to the application because of the present device state"
+            }
+            Self::ObjectDictionaryGenerationFailed => {
+                "object dictionary dynamic generation failed or no object dictionary is present"
+            }
+            Self::NoDataAvailable => "no data available",
+            Self::Other(_) => "unknown or manufacturer specific abort code",
         }
     }
 }
 
+impl std::fmt::Display for AbortCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({:#010x})", self.description(), self.encode())
+    }
+}
+
+/// Data types from CiA 301 table 44, numbered like their index in the object dictionary
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DataType {
+    Boolean,
+    Integer8,
+    Integer16,
+    Integer32,
+    Unsigned8,
+    Unsigned16,
+    Unsigned32,
+    Real32,
+    VisibleString,
+    OctetString,
+    UnicodeString,
+    TimeOfDay,
+    TimeDifference,
+    Domain,
+    Integer24,
+    Real64,
+    Integer40,
+    Integer48,
+    Integer56,
+    Integer64,
+    Unsigned24,
+    Unsigned40,
+    Unsigned48,
+    Unsigned56,
+    Unsigned64,
+    /// Complex (eg. 0x0020 PDO communication parameter) or manufacturer specific types
+    Other(u16),
+}
+
+impl DataType {
+    pub fn decode(index: u16) -> Self {
+        match index {
+            0x0001 => Self::Boolean,
+            0x0002 => Self::Integer8,
+            0x0003 => Self::Integer16,
+            0x0004 => Self::Integer32,
+            0x0005 => Self::Unsigned8,
+            0x0006 => Self::Unsigned16,
+            0x0007 => Self::Unsigned32,
+            0x0008 => Self::Real32,
+            0x0009 => Self::VisibleString,
+            0x000A => Self::OctetString,
+            0x000B => Self::UnicodeString,
+            0x000C => Self::TimeOfDay,
+            0x000D => Self::TimeDifference,
+            0x000F => Self::Domain,
+            0x0010 => Self::Integer24,
+            0x0011 => Self::Real64,
+            0x0012 => Self::Integer40,
+            0x0013 => Self::Integer48,
+            0x0014 => Self::Integer56,
+            0x0015 => Self::Integer64,
+            0x0016 => Self::Unsigned24,
+            0x0018 => Self::Unsigned40,
+            0x0019 => Self::Unsigned48,
+            0x001A => Self::Unsigned56,
+            0x001B => Self::Unsigned64,
+            other => Self::Other(other),
+        }
+    }
+
+    pub fn encode(&self) -> u16 {
+        match self {
+            Self::Boolean => 0x0001,
+            Self::Integer8 => 0x0002,
+            Self::Integer16 => 0x0003,
+            Self::Integer32 => 0x0004,
+            Self::Unsigned8 => 0x0005,
+            Self::Unsigned16 => 0x0006,
+            Self::Unsigned32 => 0x0007,
+            Self::Real32 => 0x0008,
+            Self::VisibleString => 0x0009,
+            Self::OctetString => 0x000A,
+            Self::UnicodeString => 0x000B,
+            Self::TimeOfDay => 0x000C,
+            Self::TimeDifference => 0x000D,
+            Self::Domain => 0x000F,
+            Self::Integer24 => 0x0010,
+            Self::Real64 => 0x0011,
+            Self::Integer40 => 0x0012,
+            Self::Integer48 => 0x0013,
+            Self::Integer56 => 0x0014,
+            Self::Integer64 => 0x0015,
+            Self::Unsigned24 => 0x0016,
+            Self::Unsigned40 => 0x0018,
+            Self::Unsigned48 => 0x0019,
+            Self::Unsigned56 => 0x001A,
+            Self::Unsigned64 => 0x001B,
+            Self::Other(index) => *index,
+        }
+    }
+
+    /// Encoded size in bytes, None for variable length types
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            Self::Boolean | Self::Integer8 | Self::Unsigned8 => Some(1),
+            Self::Integer16 | Self::Unsigned16 => Some(2),
+            Self::Integer24 | Self::Unsigned24 => Some(3),
+            Self::Integer32 | Self::Unsigned32 | Self::Real32 => Some(4),
+            Self::Integer40 | Self::Unsigned40 => Some(5),
+            Self::Integer48 | Self::Unsigned48 | Self::TimeOfDay | Self::TimeDifference => Some(6),
+            Self::Integer56 | Self::Unsigned56 => Some(7),
+            Self::Integer64 | Self::Unsigned64 | Self::Real64 => Some(8),
+            _ => None,
+        }
+    }
+
+    pub fn is_signed_integer(&self) -> bool {
+        matches!(
+            self,
+            Self::Integer8
+                | Self::Integer16
+                | Self::Integer24
+                | Self::Integer32
+                | Self::Integer40
+                | Self::Integer48
+                | Self::Integer56
+                | Self::Integer64
+        )
+    }
+}
+
+/// Access type of an object dictionary entry, as spelled in EDS files
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccessType {
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+    /// read/write, preferably mapped into a TPDO
+    ReadWriteRead,
+    /// read/write, preferably mapped into an RPDO
+    ReadWriteWrite,
+    /// read only, value never changes
+    Const,
+}
+
+impl AccessType {
+    /// Case insensitive, like EDS editors write it
+    pub fn decode(s: &str) -> Result<Self, CanOpenError> {
+        match s.to_ascii_lowercase().as_str() {
+            "ro" => Ok(Self::ReadOnly),
+            "wo" => Ok(Self::WriteOnly),
+            "rw" => Ok(Self::ReadWrite),
+            "rwr" => Ok(Self::ReadWriteRead),
+            "rww" => Ok(Self::ReadWriteWrite),
+            "const" => Ok(Self::Const),
+            _ => Err(CanOpenError::ParseError(format!("unknown access type {s:?}"))),
+        }
+    }
+
+    pub fn encode(&self) -> &'static str {
+        match self {
+            Self::ReadOnly => "ro",
+            Self::WriteOnly => "wo",
+            Self::ReadWrite => "rw",
+            Self::ReadWriteRead => "rwr",
+            Self::ReadWriteWrite => "rww",
+            Self::Const => "const",
+        }
+    }
+
+    pub fn is_readable(&self) -> bool {
+        !matches!(self, Self::WriteOnly)
+    }
+
+    pub fn is_writable(&self) -> bool {
+        !matches!(self, Self::ReadOnly | Self::Const)
+    }
+}
+
+/// Object code from CiA 301 table 42
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ObjectType {
+    Null = 0x0,
+    Domain = 0x2,
+    DefType = 0x5,
+    DefStruct = 0x6,
+    Var = 0x7,
+    Array = 0x8,
+    Record = 0x9,
+}
+
+impl ObjectType {
+    pub fn decode(code: u8) -> Result<Self, CanOpenError> {
+        match code {
+            0x0 => Ok(Self::Null),
+            0x2 => Ok(Self::Domain),
+            0x5 => Ok(Self::DefType),
+            0x6 => Ok(Self::DefStruct),
+            0x7 => Ok(Self::Var),
+            0x8 => Ok(Self::Array),
+            0x9 => Ok(Self::Record),
+            _ => Err(CanOpenError::ParseError(format!(
+                "unknown object type {code:#x}"
+            ))),
+        }
+    }
+
+    /// Array, record and struct objects have sub-indices, the others are a single value
+    pub fn has_sub_objects(&self) -> bool {
+        matches!(self, Self::Array | Self::Record | Self::DefStruct)
+    }
+}
@@ -14,6 +14,22 @@
 //! ✅ rusty types for most CANOpen messages
 //! ✅ send/receive messages via socketcan
 //! ✅ nice SDO wrapper.
+//! ✅ streaming SDO transfers with progress and cancellation
+//! ✅ SDO retry policy with backoff
+//! ✅ read-through cache for const/ro objects
+//! ✅ RemoteNode handle: SDO, NMT, heartbeat/EMCY state and PDO configuration of one node
+//! ✅ object access by EDS parameter name
+//! ✅ EMCY consumer, tracking active errors per node
+//! ✅ SYNC producer, per-cycle PDO snapshots
+//! ✅ TIME producer/consumer
+//! ✅ LSS master (CiA 305), including fastscan, and LSS slave
+//! ✅ EDS parser (CiA 306), DCF reader/writer
+//! ✅ XDD/XDC parser (CiA 311), converting to EDS
+//! ✅ typed OD accessors, generated from an EDS at build time
+//! ✅ whole node dump to JSON/DCF
+//! ✅ config diff of a node against its DCF/EDS, or against another node
+//! ✅ network scan, listing nodes with their identity
+//! ✅ SDO channels on custom COB-IDs (0x1200-0x12FF)
 //! we're still missing:
 //! ❌CANOpen node (read/writable Object Dictionary, respecting the OD configs)
 //! ❌MPDO support
@@ -24,7 +40,26 @@ use std::num::TryFromIntError;
 use binrw::{binrw, BinRead, BinWrite};
 use socketcan::{EmbeddedFrame, Frame, Id, Socket};
 
+pub mod cache;
+pub mod codegen;
+pub mod dcf;
+pub mod diff;
+pub mod dump;
+pub mod eds;
+pub mod emcy;
 pub mod enums;
+pub mod lss;
+pub mod named;
+pub mod od;
+pub mod pdo;
+mod periodic;
+pub mod remote;
+pub mod scan;
+pub mod sdo;
+pub mod sync;
+pub mod time;
+pub mod value;
+pub mod xdd;
 
 trait FrameRW {
     fn encode(&self, frame: &mut socketcan::CanFrame);
@@ -97,16 +132,16 @@ pub enum NmtFunction {
 
 #[binrw]
 #[brw(little)]
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Emergency {
     #[brw(ignore)]
     node_id: u8,
 
-    #[br(temp)]
-    #[bw(calc =  enums::EmergencyErrorCode::encode(error_code))]
+    // the exact code from the wire, `error_code` only keeps the category
+    // (eg. all of 0xFF00..=0xFFFF are `DeviceSpecific`)
     error_code_raw: u16,
 
-    #[br(try_calc = enums::EmergencyErrorCode::decode(error_code_raw))]
+    #[br(calc = enums::EmergencyErrorCode::decode(error_code_raw))]
     #[bw(ignore)]
     error_code: enums::EmergencyErrorCode,
 
@@ -127,19 +162,65 @@ impl Emergency {
         error_code: enums::EmergencyErrorCode,
         error_register: Vec<enums::EmergencyErrorRegister>,
         vendor_specific: &[u8],
-    ) -> Self {
-        Self {
+    ) -> Result<Self, CanOpenError> {
+        Ok(Self {
             node_id,
+            error_code_raw: error_code.encode(),
             error_code,
             error_register,
-            vendor_specific: Self::to_vendor_specific(vendor_specific),
-        }
+            vendor_specific: Self::to_vendor_specific(vendor_specific)?,
+        })
+    }
+
+    /// Like `new`, but keeps the exact 16 bit error code,
+    /// eg. a manufacturer specific code from a drive manual
+    pub fn with_raw_code(
+        node_id: u8,
+        error_code_raw: u16,
+        error_register: Vec<enums::EmergencyErrorRegister>,
+        vendor_specific: &[u8],
+    ) -> Result<Self, CanOpenError> {
+        Ok(Self {
+            node_id,
+            error_code_raw,
+            error_code: enums::EmergencyErrorCode::decode(error_code_raw),
+            error_register,
+            vendor_specific: Self::to_vendor_specific(vendor_specific)?,
+        })
+    }
+
+    pub fn node_id(&self) -> u8 {
+        self.node_id
+    }
+
+    /// Category of the error code
+    pub fn error_code(&self) -> &enums::EmergencyErrorCode {
+        &self.error_code
     }
 
-    fn to_vendor_specific(data: &[u8]) -> [u8; 5] {
+    /// Error code exactly as sent by the node
+    pub fn error_code_raw(&self) -> u16 {
+        self.error_code_raw
+    }
+
+    pub fn error_register(&self) -> &[enums::EmergencyErrorRegister] {
+        &self.error_register
+    }
+
+    pub fn vendor_specific(&self) -> &[u8; 5] {
+        &self.vendor_specific
+    }
+
+    fn to_vendor_specific(data: &[u8]) -> Result<[u8; 5], CanOpenError> {
+        if data.len() > 5 {
+            return Err(CanOpenError::BadMessage(format!(
+                "got {} bytes of vendor specific EMCY data, expected at most 5 bytes",
+                data.len()
+            )));
+        }
         let mut arr = [0u8; 5];
         arr[0..data.len()].copy_from_slice(data);
-        arr
+        Ok(arr)
     }
 }
 
@@ -539,8 +620,7 @@ impl SdoCmdAbortTransfer {
         );
         let sub_index = frame.data()[3];
         let abort_code_u32 = u32::from_le_bytes(frame.data()[4..8].try_into().unwrap());
-        let abort_code = enums::AbortCode::decode(abort_code_u32)
-            .ok_or_else(|| CanOpenError::ParseError(format!("invalid abort code: {abort_code_u32}")))?;
+        let abort_code = enums::AbortCode::decode(abort_code_u32);
 
         Ok(Self {
             index,
@@ -631,18 +711,28 @@ impl Sdo {
 
 impl FrameRW for Sdo {
     fn decode(frame: &socketcan::CanFrame) -> Result<Sdo, CanOpenError> {
-        let data = frame.data();
-
         let id = id_as_raw_std(frame)?;
         if !(0x580..=0x5FF).contains(&id) && !(0x600..=0x67F).contains(&id) {
             return Err(CanOpenError::BadMessage(format!(
                 "{id} is not an SDO can id"
             ))); // Not a valid SDO COB-ID
         }
+        Self::decode_from(frame, (id & 0x7F) as u8, ReqRes::from_u16_sdo(id))
+    }
 
-        let node_id = (id & 0x7F) as u8;
-        let reqres = ReqRes::from_u16_sdo(id);
+    fn encode(&self, frame: &mut socketcan::CanFrame) {
+        self.encode_with_id(frame, (self.node_id as u16) + self.reqres.to_u16_sdo());
+    }
+}
 
+impl Sdo {
+    /// Decodes the payload of a frame already known to be an SDO, eg. one on a configured `sdo::SdoChannel`
+    pub(crate) fn decode_from(
+        frame: &socketcan::CanFrame,
+        node_id: u8,
+        reqres: ReqRes,
+    ) -> Result<Sdo, CanOpenError> {
+        let data = frame.data();
         let command_spec = SdoCmdSpec::from_byte(data[0], reqres)?;
         let command = match (reqres, command_spec) {
             (ReqRes::Req, SdoCmdSpec::InitiateDownload) => {
@@ -682,8 +772,8 @@ impl FrameRW for Sdo {
         Ok(sdo)
     }
 
-    fn encode(&self, frame: &mut socketcan::CanFrame) {
-        frame.set_id(u16_as_id((self.node_id as u16) + self.reqres.to_u16_sdo()));
+    pub(crate) fn encode_with_id(&self, frame: &mut socketcan::CanFrame, can_id: u16) {
+        frame.set_id(u16_as_id(can_id));
         match &self.command {
             SdoCmd::InitiateUploadRx(inner) => inner.encode(frame),
             SdoCmd::InitiateDownloadRx(inner) => inner.encode(frame),
@@ -699,7 +789,7 @@ impl FrameRW for Sdo {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(u8)]
 pub enum GuardStatus {
     Boot = 0x00,
@@ -749,6 +839,19 @@ impl Guard {
             status,
         }
     }
+
+    pub fn node_id(&self) -> u8 {
+        self.node_id
+    }
+
+    pub fn toggle(&self) -> bool {
+        self.toggle
+    }
+
+    /// `GuardStatus::Boot` for boot-up messages
+    pub fn status(&self) -> GuardStatus {
+        self.status
+    }
 }
 
 impl FrameRW for Guard {
@@ -764,6 +867,10 @@ impl FrameRW for Guard {
         }
         Guard::read(&mut std::io::Cursor::new(&data))
             .map_err(|e| CanOpenError::ParseError(format!("no parse: {e}")))
+            .map(|mut m| {
+                m.node_id = (id - 0x700) as u8;
+                m
+            })
     }
 
     fn encode(&self, frame: &mut socketcan::CanFrame) {
@@ -790,10 +897,8 @@ impl ReqRes {
             ReqRes::Res => 0x580,
         }
     }
-    // TODO: SDO can go over other CAN IDs
-    // (page 126 of cia301)
-    // this just supports the default one
-    // as I have not seen other SDOs in the wild
+    // only the default SDO channel, other CAN IDs (page 126 of cia301)
+    // go through `sdo::SdoChannel`, see `Conn::set_sdo_channel`
     pub fn from_u16_sdo(id: u16) -> Self {
         if id & 0x780 == 0x580 {
             ReqRes::Res
@@ -803,11 +908,11 @@ impl ReqRes {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Pdo {
     node_id: u8,
     pdo_index: u8, // PDO index (1 to 4)
-    reqres: ReqRes,
+    cob_id: u16,
     data: Vec<u8>, // Data (1 to 8 bytes)
 }
 
@@ -822,10 +927,27 @@ impl Pdo {
         Ok(Self {
             node_id,
             pdo_index,
-            reqres: ReqRes::Req,
+            cob_id: node_id as u16 + ((pdo_index as u16 + 1) << 8),
             data: data.to_owned(),
         })
     }
+
+    pub fn node_id(&self) -> u8 {
+        self.node_id
+    }
+
+    pub fn pdo_index(&self) -> u8 {
+        self.pdo_index
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The COB-ID as received (or as `new` computed it)
+    pub fn cob_id(&self) -> u16 {
+        self.cob_id
+    }
 }
 
 impl FrameRW for Pdo {
@@ -843,44 +965,147 @@ impl FrameRW for Pdo {
 
         Ok(Pdo {
             pdo_index,
-            reqres,
             node_id,
+            cob_id: id,
             data,
         })
     }
 
     fn encode(&self, frame: &mut socketcan::CanFrame) {
-        let id = (self.pdo_index as u16 + if self.reqres == ReqRes::Req { 1 } else { 0 }) << 8;
-        frame.set_id(u16_as_id(self.node_id as u16 + id));
+        frame.set_id(u16_as_id(self.cob_id()));
         // unwrap wont panic here, we guarantee data is between 1 and 8 bytes
         frame.set_data(&self.data).unwrap();
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 /// Sync messages are usually sent at regular intervals.
 /// "Synchronous events" are often driven by sync messages.
 /// For example, you can configure PDOs to be sent after every sync message.
-pub struct Sync;
+/// If the synchronous counter overflow value (0x1019) is set, every SYNC
+/// carries a 1 byte counter, counting from 1 up to the overflow value.
+/// See `sync::SyncProducer` for sending them periodically.
+pub struct Sync {
+    pub counter: Option<u8>,
+    cob_id: u16,
+}
+
+impl Default for Sync {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl Sync {
+    pub fn new(counter: Option<u8>) -> Self {
+        Self::with_cob_id(0x80, counter)
+    }
+
+    /// SYNC on a non-default COB-ID (bits 0..=10 of 0x1005)
+    pub fn with_cob_id(cob_id: u16, counter: Option<u8>) -> Self {
+        Self { counter, cob_id }
+    }
+
+    pub fn cob_id(&self) -> u16 {
+        self.cob_id
+    }
+}
 
 impl FrameRW for Sync {
+    /// Any COB-ID is taken, which one carries SYNCs is up to the `Conn` (`Conn::set_sync_cob_id`)
     fn decode(frame: &socketcan::CanFrame) -> Result<Sync, CanOpenError> {
         let id = id_as_raw_std(frame)?;
-        if id != 0x80 {
-            Err(CanOpenError::BadMessage(format!("not a SYNC cob-id: {id}")))
-        } else if !frame.data().is_empty() {
-            Err(CanOpenError::BadMessage(format!(
-                "data section of SYNC message should be empty, found {} bytes",
+        match frame.data() {
+            [] => Ok(Sync::with_cob_id(id, None)),
+            [counter] => Ok(Sync::with_cob_id(id, Some(*counter))),
+            data => Err(CanOpenError::BadMessage(format!(
+                "data section of SYNC message should be empty or a 1 byte counter, found {} bytes",
+                data.len()
+            ))),
+        }
+    }
+
+    fn encode(&self, frame: &mut socketcan::CanFrame) {
+        frame.set_id(u16_as_id(self.cob_id));
+        match self.counter {
+            Some(counter) => frame.set_data(&[counter]).unwrap(),
+            None => frame.set_data(&[]).unwrap(),
+        }
+    }
+}
+
+/// TIME_OF_DAY, as broadcast in TIME messages (COB-ID 0x100)
+/// Milliseconds after midnight (28 bits) and days since 1984-01-01.
+/// Convert from/to `std::time::SystemTime` with `TryFrom`/`From`.
+#[binrw]
+#[brw(little)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeOfDay {
+    #[br(map = |ms: u32| ms & 0x0FFF_FFFF)]
+    #[bw(map = |ms: &u32| ms & 0x0FFF_FFFF)]
+    pub ms_since_midnight: u32,
+    pub days_since_1984: u16,
+}
+
+impl TimeOfDay {
+    /// 1984-01-01 00:00:00 UTC, as seconds since the unix epoch
+    const EPOCH_UNIX_SECS: u64 = 441_763_200;
+    const MS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+    pub fn new(ms_since_midnight: u32, days_since_1984: u16) -> Self {
+        Self {
+            ms_since_midnight,
+            days_since_1984,
+        }
+    }
+}
+
+impl From<TimeOfDay> for std::time::SystemTime {
+    fn from(t: TimeOfDay) -> Self {
+        let ms = t.days_since_1984 as u64 * TimeOfDay::MS_PER_DAY + t.ms_since_midnight as u64;
+        std::time::UNIX_EPOCH
+            + std::time::Duration::from_secs(TimeOfDay::EPOCH_UNIX_SECS)
+            + std::time::Duration::from_millis(ms)
+    }
+}
+
+impl TryFrom<std::time::SystemTime> for TimeOfDay {
+    type Error = CanOpenError;
+
+    /// Fails for times before 1984 or too far in the future (after 2163)
+    fn try_from(time: std::time::SystemTime) -> Result<Self, Self::Error> {
+        let epoch = std::time::UNIX_EPOCH + std::time::Duration::from_secs(Self::EPOCH_UNIX_SECS);
+        let ms = time
+            .duration_since(epoch)
+            .map_err(|_| CanOpenError::OverflowError("TIME_OF_DAY starts at 1984".to_owned()))?
+            .as_millis();
+        let days = u16::try_from(ms / Self::MS_PER_DAY as u128)
+            .map_err(|e| CanOpenError::OverflowError(e.to_string()))?;
+        Ok(Self::new((ms % Self::MS_PER_DAY as u128) as u32, days))
+    }
+}
+
+impl FrameRW for TimeOfDay {
+    fn decode(frame: &socketcan::CanFrame) -> Result<TimeOfDay, CanOpenError> {
+        let id = id_as_raw_std(frame)?;
+        if id != 0x100 {
+            return Err(CanOpenError::BadMessage(format!("not a TIME cob-id: {id}")));
+        }
+        if frame.data().len() != 6 {
+            return Err(CanOpenError::ParseError(format!(
+                "TIME message needs 6 bytes, got {}",
                 frame.data().len()
-            )))
-        } else {
-            Ok(Sync {})
+            )));
         }
+        TimeOfDay::read(&mut std::io::Cursor::new(frame.data()))
+            .map_err(|e| CanOpenError::ParseError(format!("binrw err: {e}")))
     }
 
     fn encode(&self, frame: &mut socketcan::CanFrame) {
-        frame.set_id(u16_as_id(0x80));
-        frame.set_data(&[]).unwrap();
+        frame.set_id(u16_as_id(0x100));
+        let mut c = std::io::Cursor::new(Vec::new());
+        self.write(&mut c).unwrap();
+        frame.set_data(c.get_ref()).unwrap();
     }
 }
 
@@ -892,6 +1117,8 @@ pub enum Message {
     Pdo(Pdo),
     Sdo(Sdo),
     Guard(Guard),
+    Time(TimeOfDay),
+    Lss(lss::Lss),
 }
 
 use thiserror::Error;
@@ -922,13 +1149,56 @@ pub enum CanOpenError {
     #[error("Not yet implemented: {0}")]
     NotYetImplemented(String),
 
-    #[error("SDO AbortTransfer error, abort code: {0:?}")]
-    SdoAbortTransfer(enums::AbortCode),
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("No object called {name:?}{}", did_you_mean(.suggestions))]
+    UnknownName {
+        name: String,
+        suggestions: Vec<String>,
+    },
+
+    #[error("{name:?} could be any of {}", .candidates.join(", "))]
+    AmbiguousName {
+        name: String,
+        /// Qualified names (or index names where those collide), each of which `od::ObjectDictionary::find` resolves
+        candidates: Vec<String>,
+    },
+
+    #[error("SDO transfer of {index:#06x}sub{sub_index} aborted: {abort_code}")]
+    SdoAbortTransfer {
+        index: u16,
+        sub_index: u8,
+        abort_code: enums::AbortCode,
+    },
+
+    #[error("SDO transfer of {index:#06x}sub{sub_index} cancelled")]
+    SdoCancelled { index: u16, sub_index: u8 },
+
+    #[error("LSS {command} failed with error code {error_code}, manufacturer error {spec_error}")]
+    LssConfigurationFailed {
+        command: &'static str,
+        error_code: u8,
+        spec_error: u8,
+    },
+
+    #[error("EDS line {line}: {message}")]
+    EdsError { line: usize, message: String },
+
+    #[error("XDD line {line}: {message}")]
+    XddError { line: usize, message: String },
 
     #[error("IO Error: {0}")]
     IOError(std::io::Error),
 }
 
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        suggestions => format!(", did you mean {}?", suggestions.join(", ")),
+    }
+}
+
 /// CAN connection. Connects on `Conn::new()`
 /// Writing/reading a single CAN frame is thread safe,
 /// since socketcan guarantees atomic frame reads and writes.
@@ -937,17 +1207,104 @@ pub enum CanOpenError {
 #[derive(Debug)]
 pub struct Conn {
     socket: socketcan::CanSocket,
+    sdo_timeout: std::time::Duration,
+    sdo_channels: std::collections::BTreeMap<u8, sdo::SdoChannel>,
+    sdo_retry_policy: sdo::RetryPolicy,
+    sync_cob_id: u16,
+}
+
+/// How long a whole SDO transfer (all of its segments) may take, see `Conn::set_sdo_timeout`
+pub const DEFAULT_SDO_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// When an SDO transfer times out: as a whole, or per request/response (for streams)
+#[derive(Clone, Copy, Debug)]
+enum SdoDeadline {
+    Transfer {
+        at: std::time::Instant,
+        timeout: std::time::Duration,
+    },
+    Segment(std::time::Duration),
+}
+
+impl SdoDeadline {
+    /// The whole transfer has to be done within `timeout` from now
+    fn transfer(timeout: std::time::Duration) -> Self {
+        SdoDeadline::Transfer {
+            at: std::time::Instant::now() + timeout,
+            timeout,
+        }
+    }
+
+    /// Deadline for the next response
+    fn next(&self) -> std::time::Instant {
+        match self {
+            SdoDeadline::Transfer { at, .. } => *at,
+            SdoDeadline::Segment(timeout) => std::time::Instant::now() + *timeout,
+        }
+    }
+
+    /// What timed out, for `CanOpenError::Timeout`
+    fn timeout(&self) -> std::time::Duration {
+        match self {
+            SdoDeadline::Transfer { timeout, .. } | SdoDeadline::Segment(timeout) => *timeout,
+        }
+    }
 }
 
 impl Conn {
     pub fn new(interface_name: &str) -> Result<Self, CanOpenError> {
         let socket = socketcan::CanSocket::open(interface_name).expect("no iface");
-        Ok(Conn { socket })
+        Ok(Conn {
+            socket,
+            sdo_timeout: DEFAULT_SDO_TIMEOUT,
+            sdo_channels: std::collections::BTreeMap::new(),
+            sdo_retry_policy: sdo::RetryPolicy::default(),
+            sync_cob_id: 0x80,
+        })
     }
 
     pub fn recv(&self) -> Result<Message, CanOpenError> {
         let frame = self.socket.read_frame().map_err(CanOpenError::IOError)?;
-        Self::decode(&frame)
+        self.decode(&frame)
+    }
+
+    /// Like `recv`, but gives up with `CanOpenError::Timeout` after `timeout`
+    pub fn recv_timeout(&self, timeout: std::time::Duration) -> Result<Message, CanOpenError> {
+        let frame = self
+            .socket
+            .read_frame_timeout(timeout)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => {
+                    CanOpenError::Timeout(timeout.as_millis() as u64)
+                }
+                _ => CanOpenError::IOError(e),
+            })?;
+        self.decode(&frame)
+    }
+
+    /// Receives until `matches` returns `Some`, or returns `None` once `deadline` passes.
+    /// Frames we can't decode are skipped, they're most likely not meant for us.
+    pub(crate) fn recv_matching<T>(
+        &self,
+        deadline: std::time::Instant,
+        mut matches: impl FnMut(Message) -> Option<T>,
+    ) -> Result<Option<T>, CanOpenError> {
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            match self.recv_timeout(remaining) {
+                Ok(message) => {
+                    if let Some(t) = matches(message) {
+                        return Ok(Some(t));
+                    }
+                }
+                Err(CanOpenError::Timeout(_)) => return Ok(None),
+                Err(e @ CanOpenError::IOError(_)) => return Err(e),
+                Err(_) => continue,
+            }
+        }
     }
 
     pub fn set_read_timeout(&self, t: std::time::Duration) -> Result<(), CanOpenError> {
@@ -962,30 +1319,232 @@ impl Conn {
             .map_err(CanOpenError::IOError)
     }
 
-    fn send_sdo_acked(&self, message: Sdo, node_id: u8) -> Result<Sdo, CanOpenError> {
-        self.send(&Message::Sdo(message.clone()))?;
+    /// Overall deadline for each SDO transfer, unlike `set_read_timeout` which applies per frame.
+    /// Once it passes, the transfer is aborted with `SdoProtocolTimedOut` and fails with `CanOpenError::Timeout`.
+    /// Streams (`sdo_write_from`, `sdo_read_into`) get it for every segment instead.
+    pub fn set_sdo_timeout(&mut self, t: std::time::Duration) {
+        self.sdo_timeout = t;
+    }
+
+    /// Which COB-ID `recv` decodes as SYNC, bits 0..=10 of the nodes' 0x1005. Default 0x80.
+    pub fn set_sync_cob_id(&mut self, cob_id: u16) {
+        self.sync_cob_id = cob_id;
+    }
+
+    pub fn sync_cob_id(&self) -> u16 {
+        self.sync_cob_id
+    }
+
+    pub fn sdo_timeout(&self) -> std::time::Duration {
+        self.sdo_timeout
+    }
+
+    /// Retries for `sdo_read` and `sdo_write` (and the typed variants).
+    /// Streaming transfers are never retried, their data can't be rewound.
+    pub fn set_sdo_retry_policy(&mut self, policy: sdo::RetryPolicy) {
+        self.sdo_retry_policy = policy;
+    }
+
+    pub fn sdo_retry_policy(&self) -> &sdo::RetryPolicy {
+        &self.sdo_retry_policy
+    }
+
+    /// Runs `transfer` until it succeeds or the retry policy gives up, logging every attempt
+    fn with_sdo_retries<T>(
+        &mut self,
+        direction: &str,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+        mut transfer: impl FnMut(&mut Self) -> Result<T, CanOpenError>,
+    ) -> Result<T, CanOpenError> {
+        let policy = self.sdo_retry_policy.clone();
+        let max_attempts = policy.max_attempts();
+        let mut attempt = 1;
         loop {
-            let resp = self.recv()?;
-            if Self::is_sdo_ack(&resp, &message.command, node_id)? {
-                match resp {
-                    Message::Sdo(sdo) => return Ok(sdo),
-                    _ => unreachable!(),
+            log::debug!(
+                "SDO {direction} of {index:#06x}sub{sub_index} on node {node_id}, attempt {attempt}/{max_attempts}"
+            );
+            match transfer(self) {
+                Err(e) if attempt < max_attempts && policy.is_retryable(&e) => {
+                    let backoff = policy.backoff(attempt);
+                    log::warn!(
+                        "SDO {direction} of {index:#06x}sub{sub_index} on node {node_id} failed (attempt {attempt}/{max_attempts}): {e}, retrying in {backoff:?}"
+                    );
+                    std::thread::sleep(backoff);
+                    // late responses to the failed attempt would confuse the next one
+                    self.recv_matching(
+                        std::time::Instant::now() + std::time::Duration::from_millis(1),
+                        |_| None::<()>,
+                    )?;
+                    attempt += 1;
                 }
-            };
+                Err(e) => {
+                    if attempt > 1 {
+                        log::error!(
+                            "SDO {direction} of {index:#06x}sub{sub_index} on node {node_id} failed after {attempt} attempts: {e}"
+                        );
+                    }
+                    return Err(e);
+                }
+                Ok(t) => {
+                    if attempt > 1 {
+                        log::info!(
+                            "SDO {direction} of {index:#06x}sub{sub_index} on node {node_id} succeeded on attempt {attempt}"
+                        );
+                    }
+                    return Ok(t);
+                }
+            }
         }
     }
 
-    fn is_sdo_ack(message: &Message, command: &SdoCmd, node_id: u8) -> Result<bool, CanOpenError> {
-        match message {
-            Message::Sdo(sdo) if sdo.node_id == node_id => match &sdo.command {
-                SdoCmd::AbortTransfer(e) => Err(CanOpenError::SdoAbortTransfer(e.abort_code.clone())),
-                cmd if SdoCmd::is_response_to(command, cmd) => Ok(true),
-                _ => Ok(false),
+    /// SDO messages to/from `channel.node_id` go over the channel's COB-IDs from now on,
+    /// replacing any channel set before for that node id. Frames on the channel's COB-IDs are
+    /// always decoded as SDOs of that node, whatever they'd be otherwise.
+    pub fn set_sdo_channel(&mut self, channel: sdo::SdoChannel) -> Result<(), CanOpenError> {
+        if !channel.is_valid() {
+            return Err(CanOpenError::BadMessage(format!(
+                "SDO channel of node {} is not valid",
+                channel.node_id
+            )));
+        }
+        channel.request_can_id()?;
+        channel.response_can_id()?;
+        self.sdo_channels.insert(channel.node_id, channel);
+        Ok(())
+    }
+
+    /// Goes back to the default channel for `node_id`
+    pub fn remove_sdo_channel(&mut self, node_id: u8) -> Option<sdo::SdoChannel> {
+        self.sdo_channels.remove(&node_id)
+    }
+
+    /// The channel SDOs to/from `node_id` use
+    pub fn sdo_channel(&self, node_id: u8) -> sdo::SdoChannel {
+        self.sdo_channels
+            .get(&node_id)
+            .copied()
+            .unwrap_or_else(|| sdo::SdoChannel::default_for(node_id))
+    }
+
+    /// Tells the server to drop the transfer of `index`/`sub_index`
+    fn send_sdo_abort(
+        &self,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+        abort_code: enums::AbortCode,
+    ) -> Result<(), CanOpenError> {
+        self.send(&Message::Sdo(Sdo {
+            node_id,
+            reqres: ReqRes::Req,
+            command: SdoCmd::AbortTransfer(SdoCmdAbortTransfer {
+                index,
+                sub_index,
+                abort_code,
+            }),
+        }))
+    }
+
+    /// Aborts a transfer the server got wrong, returns the error to fail it with
+    fn client_abort(
+        &self,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+        abort_code: enums::AbortCode,
+    ) -> CanOpenError {
+        match self.send_sdo_abort(node_id, index, sub_index, abort_code) {
+            Ok(()) => CanOpenError::SdoAbortTransfer {
+                index,
+                sub_index,
+                abort_code,
             },
-            _ => Ok(false),
+            Err(e) => e,
+        }
+    }
+
+    /// Aborts a transfer our side of which failed with `e`
+    fn io_abort(&self, node_id: u8, index: u16, sub_index: u8, e: std::io::Error) -> CanOpenError {
+        match self.send_sdo_abort(node_id, index, sub_index, enums::AbortCode::GeneralError) {
+            Ok(()) => CanOpenError::IOError(e),
+            Err(abort_error) => abort_error,
         }
     }
 
+    /// Aborts a transfer the user cancelled
+    fn cancel(&self, node_id: u8, index: u16, sub_index: u8) -> CanOpenError {
+        match self.send_sdo_abort(node_id, index, sub_index, enums::AbortCode::GeneralError) {
+            Ok(()) => CanOpenError::SdoCancelled { index, sub_index },
+            Err(e) => e,
+        }
+    }
+
+    /// Sends one request of the transfer of `index`/`sub_index`, and waits for its response until `deadline`.
+    /// Responses that don't fit the request abort the transfer: the wrong command with
+    /// `InvalidClientServerCommandSpecifier`, the wrong index or sub-index with `GeneralError`.
+    fn send_sdo_acked(
+        &self,
+        message: Sdo,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+        deadline: SdoDeadline,
+    ) -> Result<Sdo, CanOpenError> {
+        self.send(&Message::Sdo(message.clone()))?;
+        let response = self.recv_matching(deadline.next(), |resp| match resp {
+            Message::Sdo(sdo) if sdo.node_id == node_id && sdo.reqres == ReqRes::Res => Some(sdo),
+            _ => None,
+        })?;
+        let Some(response) = response else {
+            self.send_sdo_abort(
+                node_id,
+                index,
+                sub_index,
+                enums::AbortCode::SdoProtocolTimedOut,
+            )?;
+            return Err(CanOpenError::Timeout(deadline.timeout().as_millis() as u64));
+        };
+        let response_index = match &response.command {
+            SdoCmd::AbortTransfer(e) => {
+                return Err(CanOpenError::SdoAbortTransfer {
+                    index: e.index,
+                    sub_index: e.sub_index,
+                    abort_code: e.abort_code,
+                })
+            }
+            cmd if !SdoCmd::is_response_to(&message.command, cmd) => {
+                return Err(self.client_abort(
+                    node_id,
+                    index,
+                    sub_index,
+                    enums::AbortCode::InvalidClientServerCommandSpecifier,
+                ))
+            }
+            SdoCmd::InitiateDownloadTx(SdoCmdInitiateDownloadTx {
+                index: i,
+                sub_index: s,
+            })
+            | SdoCmd::InitiateUploadTx(SdoCmdInitiateUploadTx {
+                index: i,
+                sub_index: s,
+                ..
+            }) => Some((*i, *s)),
+            _ => None,
+        };
+        if response_index.is_some_and(|r| r != (index, sub_index)) {
+            return Err(self.client_abort(
+                node_id,
+                index,
+                sub_index,
+                enums::AbortCode::GeneralError,
+            ));
+        }
+        Ok(response)
+    }
+
+    /// Expedited up to 4 bytes, segmented above. Retried as `set_sdo_retry_policy` says.
     pub fn sdo_write(
         &mut self,
         node_id: u8,
@@ -993,25 +1552,76 @@ impl Conn {
         sub_index: u8,
         data: &[u8],
     ) -> Result<(), CanOpenError> {
-        match data.len() {
+        let len = data
+            .len()
+            .try_into()
+            .map_err(|e: TryFromIntError| CanOpenError::OverflowError(e.to_string()))?;
+        self.with_sdo_retries("download", node_id, index, sub_index, |conn| {
+            let deadline = SdoDeadline::transfer(conn.sdo_timeout);
+            conn.download(node_id, index, sub_index, data, len, deadline, |_| {
+                std::ops::ControlFlow::Continue(())
+            })
+        })
+    }
+
+    /// Downloads `len` bytes from `data`, without holding them all in memory.
+    /// `progress` is called after every segment, breaking from it aborts the transfer with
+    /// `GeneralError` and fails it with `CanOpenError::SdoCancelled`.
+    /// If reading `data` fails (or it ends early), the transfer is aborted too.
+    /// The SDO timeout (`set_sdo_timeout`) applies to each segment, not the whole stream.
+    pub fn sdo_write_from(
+        &mut self,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+        data: impl std::io::Read,
+        len: u32,
+        progress: impl FnMut(sdo::SdoProgress) -> std::ops::ControlFlow<()>,
+    ) -> Result<(), CanOpenError> {
+        let deadline = SdoDeadline::Segment(self.sdo_timeout);
+        self.download(node_id, index, sub_index, data, len, deadline, progress)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn download(
+        &mut self,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+        mut data: impl std::io::Read,
+        len: u32,
+        deadline: SdoDeadline,
+        mut progress: impl FnMut(sdo::SdoProgress) -> std::ops::ControlFlow<()>,
+    ) -> Result<(), CanOpenError> {
+        let total = Some(len as u64);
+        let mut buffer = [0; 7];
+        match len {
             // 0 bytes - nothing to do
             0 => Ok(()),
             // <= 4 bytes - single expedited write
             1..=4 => {
+                // nothing was sent yet, so there's no transfer to abort
+                data.read_exact(&mut buffer[..len as usize])
+                    .map_err(CanOpenError::IOError)?;
                 let message = Sdo {
                     node_id,
                     reqres: ReqRes::Req,
                     command: SdoCmd::InitiateDownloadRx(SdoCmdInitiateDownloadRx {
                         index,
                         sub_index,
-                        payload: SdoCmdInitiatePayload::Expedited(data.into()),
+                        payload: SdoCmdInitiatePayload::Expedited(buffer[..len as usize].into()),
                     }),
                 };
-                self.send_sdo_acked(message, node_id)?;
+                self.send_sdo_acked(message, node_id, index, sub_index, deadline)?;
+                // nothing left to cancel
+                let _ = progress(sdo::SdoProgress {
+                    done: len as u64,
+                    total,
+                });
                 Ok(())
             }
             // > 4 bytes - segmented write
-            n => {
+            len => {
                 let mut toggle = false;
                 let init_message = Sdo {
                     node_id,
@@ -1019,41 +1629,104 @@ impl Conn {
                     command: SdoCmd::InitiateDownloadRx(SdoCmdInitiateDownloadRx {
                         index,
                         sub_index,
-                        payload: SdoCmdInitiatePayload::Segmented(Some(
-                            data.len().try_into().map_err(|e: TryFromIntError| {
-                                CanOpenError::OverflowError(e.to_string())
-                            })?,
-                        )),
+                        payload: SdoCmdInitiatePayload::Segmented(Some(len)),
                     }),
                 };
-                self.send_sdo_acked(init_message, node_id)?;
-
-                for (idx_seg_start, _) in data.iter().enumerate().step_by(7) {
-                    let idx_seg_end = std::cmp::min(idx_seg_start + 7, n);
-                    let last = idx_seg_start + 7 >= n;
+                self.send_sdo_acked(init_message, node_id, index, sub_index, deadline)?;
+
+                let mut done = 0;
+                while done < len {
+                    let n = std::cmp::min(7, len - done);
+                    data.read_exact(&mut buffer[..n as usize])
+                        .map_err(|e| self.io_abort(node_id, index, sub_index, e))?;
+                    done += n;
                     let message = Sdo {
                         node_id,
                         reqres: ReqRes::Req,
                         command: SdoCmd::DownloadSegmentRx(SdoCmdDownloadSegmentRx {
                             toggle,
-                            data: data[idx_seg_start..idx_seg_end].into(),
-                            last,
+                            data: buffer[..n as usize].into(),
+                            last: done == len,
                         }),
                     };
+                    let response =
+                        self.send_sdo_acked(message, node_id, index, sub_index, deadline)?;
+                    match response.command {
+                        SdoCmd::DownloadSegmentTx(ack) if ack.toggle == toggle => {}
+                        SdoCmd::DownloadSegmentTx(_) => {
+                            return Err(self.client_abort(
+                                node_id,
+                                index,
+                                sub_index,
+                                enums::AbortCode::ToggleBitNotAlternated,
+                            ))
+                        }
+                        _ => {
+                            return Err(self.client_abort(
+                                node_id,
+                                index,
+                                sub_index,
+                                enums::AbortCode::InvalidClientServerCommandSpecifier,
+                            ))
+                        }
+                    }
+                    let progress = progress(sdo::SdoProgress {
+                        done: done as u64,
+                        total,
+                    });
+                    if progress.is_break() && done < len {
+                        return Err(self.cancel(node_id, index, sub_index));
+                    }
                     toggle = !toggle;
-                    self.send_sdo_acked(message, node_id)?;
                 }
                 Ok(())
             }
         }
     }
 
+    /// Retried as `set_sdo_retry_policy` says
     pub fn sdo_read(
         &mut self,
         node_id: u8,
         index: u16,
         sub_index: u8,
     ) -> Result<Box<[u8]>, CanOpenError> {
+        self.with_sdo_retries("upload", node_id, index, sub_index, |conn| {
+            let deadline = SdoDeadline::transfer(conn.sdo_timeout);
+            let mut buffer = Vec::new();
+            conn.upload(node_id, index, sub_index, &mut buffer, deadline, |_| {
+                std::ops::ControlFlow::Continue(())
+            })?;
+            Ok(buffer.into())
+        })
+    }
+
+    /// Uploads into `out` as the segments arrive, returns the number of bytes uploaded.
+    /// `progress` is called after every segment, breaking from it aborts the transfer with
+    /// `GeneralError` and fails it with `CanOpenError::SdoCancelled`.
+    /// If writing to `out` fails, the transfer is aborted too.
+    /// The SDO timeout (`set_sdo_timeout`) applies to each segment, not the whole stream.
+    pub fn sdo_read_into(
+        &mut self,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+        out: impl std::io::Write,
+        progress: impl FnMut(sdo::SdoProgress) -> std::ops::ControlFlow<()>,
+    ) -> Result<u64, CanOpenError> {
+        let deadline = SdoDeadline::Segment(self.sdo_timeout);
+        self.upload(node_id, index, sub_index, out, deadline, progress)
+    }
+
+    fn upload(
+        &mut self,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+        mut out: impl std::io::Write,
+        deadline: SdoDeadline,
+        mut progress: impl FnMut(sdo::SdoProgress) -> std::ops::ControlFlow<()>,
+    ) -> Result<u64, CanOpenError> {
         let res = self.send_sdo_acked(
             Sdo {
                 node_id,
@@ -1061,52 +1734,125 @@ impl Conn {
                 reqres: ReqRes::Req,
             },
             node_id,
+            index,
+            sub_index,
+            deadline,
         )?;
 
-        match res.command {
+        let maybe_len = match res.command {
             SdoCmd::InitiateUploadTx(SdoCmdInitiateUploadTx {
-                index: _,
-                sub_index: _,
                 payload: SdoCmdInitiatePayload::Expedited(data),
-            }) => Ok(data),
+                ..
+            }) => {
+                // the transfer is over already, so there's no one to tell about a failed write
+                out.write_all(&data).map_err(CanOpenError::IOError)?;
+                let done = data.len() as u64;
+                let _ = progress(sdo::SdoProgress {
+                    done,
+                    total: Some(done),
+                });
+                return Ok(done);
+            }
             SdoCmd::InitiateUploadTx(SdoCmdInitiateUploadTx {
-                index: _,
-                sub_index: _,
                 payload: SdoCmdInitiatePayload::Segmented(maybe_len),
-            }) => {
-                let mut buffer = Vec::new();
-                let mut toggle = false;
-                if let Some(len) = maybe_len {
-                    buffer.reserve(len as usize);
-                };
-                loop {
-                    let seg = self.send_sdo_acked(
-                        Sdo {
-                            reqres: ReqRes::Req,
-                            node_id,
-                            command: SdoCmd::UploadSegmentRx(SdoCmdUploadSegmentRx { toggle }),
-                        },
+                ..
+            }) => maybe_len,
+            _ => {
+                return Err(self.client_abort(
+                    node_id,
+                    index,
+                    sub_index,
+                    enums::AbortCode::InvalidClientServerCommandSpecifier,
+                ))
+            }
+        };
+        let total = maybe_len.map(|len| len as u64);
+        let length_mismatch = |done: u64, last: bool| match total {
+            Some(len) if last => done != len,
+            Some(len) => done > len,
+            None => false,
+        };
+
+        let mut done = 0;
+        let mut toggle = false;
+        loop {
+            let seg = self.send_sdo_acked(
+                Sdo {
+                    reqres: ReqRes::Req,
+                    node_id,
+                    command: SdoCmd::UploadSegmentRx(SdoCmdUploadSegmentRx { toggle }),
+                },
+                node_id,
+                index,
+                sub_index,
+                deadline,
+            )?;
+            let command = match seg.command {
+                SdoCmd::UploadSegmentTx(command) if command.toggle == toggle => command,
+                SdoCmd::UploadSegmentTx(_) => {
+                    return Err(self.client_abort(
                         node_id,
-                    )?;
-                    if let Sdo {
-                        reqres: _,
-                        node_id: _,
-                        command: SdoCmd::UploadSegmentTx(command),
-                    } = seg
-                    {
-                        buffer.extend_from_slice(&command.data);
-                        if command.last {
-                            break;
-                        }
-                    }
-                    toggle = !toggle;
+                        index,
+                        sub_index,
+                        enums::AbortCode::ToggleBitNotAlternated,
+                    ))
+                }
+                _ => {
+                    return Err(self.client_abort(
+                        node_id,
+                        index,
+                        sub_index,
+                        enums::AbortCode::InvalidClientServerCommandSpecifier,
+                    ))
                 }
-                Ok(buffer.into())
+            };
+            done += command.data.len() as u64;
+            // the server sent more (or, once done, less) than it announced
+            if length_mismatch(done, command.last) {
+                return Err(self.client_abort(
+                    node_id,
+                    index,
+                    sub_index,
+                    enums::AbortCode::DataTypeMismatchLengthMismatch,
+                ));
             }
-            _ => unreachable!(),
+            if command.last {
+                // the server considers the transfer done, so a failed write can't be aborted
+                out.write_all(&command.data)
+                    .map_err(CanOpenError::IOError)?;
+                let _ = progress(sdo::SdoProgress { done, total });
+                return Ok(done);
+            }
+            out.write_all(&command.data)
+                .map_err(|e| self.io_abort(node_id, index, sub_index, e))?;
+            if progress(sdo::SdoProgress { done, total }).is_break() {
+                return Err(self.cancel(node_id, index, sub_index));
+            }
+            toggle = !toggle;
         }
     }
 
+    /// sdo_read, decoded as `T`. Fails if the node sends the wrong number of bytes for the type.
+    pub fn sdo_read_typed<T: value::SdoValue>(
+        &mut self,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<T, CanOpenError> {
+        let data = self.sdo_read(node_id, index, sub_index)?;
+        value::decode_read(index, sub_index, &data)
+    }
+
+    pub fn sdo_write_typed<T: value::SdoValue>(
+        &mut self,
+        node_id: u8,
+        index: u16,
+        sub_index: u8,
+        value: &T,
+    ) -> Result<(), CanOpenError> {
+        self.sdo_write(node_id, index, sub_index, &value.encode())
+    }
+
     pub fn send(&self, message: &Message) -> Result<(), CanOpenError> {
         let mut frame = socketcan::CanFrame::new(
             socketcan::Id::Standard(socketcan::StandardId::new(0).unwrap()),
@@ -1114,20 +1860,48 @@ impl Conn {
         )
         .unwrap();
         match message {
-            Message::Sdo(sdo) => sdo.encode(&mut frame),
+            Message::Sdo(sdo) => match self.sdo_channels.get(&sdo.node_id) {
+                Some(channel) => {
+                    let can_id = match sdo.reqres {
+                        ReqRes::Req => channel.request_can_id()?,
+                        ReqRes::Res => channel.response_can_id()?,
+                    };
+                    sdo.encode_with_id(&mut frame, can_id)
+                }
+                None => sdo.encode(&mut frame),
+            },
             Message::Pdo(pdo) => pdo.encode(&mut frame),
             Message::Sync(sync) => sync.encode(&mut frame),
             Message::Nmt(nmt) => nmt.encode(&mut frame),
             Message::Emergency(emergency) => emergency.encode(&mut frame),
             Message::Guard(guard) => guard.encode(&mut frame),
+            Message::Time(time) => time.encode(&mut frame),
+            Message::Lss(lss) => lss.encode(&mut frame),
         }
         self.socket
             .write_frame(&frame)
             .map_err(CanOpenError::IOError)
     }
 
-    fn decode(frame: &socketcan::CanFrame) -> Result<Message, CanOpenError> {
+    fn decode(&self, frame: &socketcan::CanFrame) -> Result<Message, CanOpenError> {
         let id = id_as_raw_std(frame).unwrap();
+        for channel in self.sdo_channels.values() {
+            let reqres = if channel.request_can_id().ok() == Some(id) {
+                ReqRes::Req
+            } else if channel.response_can_id().ok() == Some(id) {
+                ReqRes::Res
+            } else {
+                continue;
+            };
+            return Ok(Message::Sdo(Sdo::decode_from(
+                frame,
+                channel.node_id,
+                reqres,
+            )?));
+        }
+        if id == self.sync_cob_id {
+            return Ok(Message::Sync(Sync::decode(frame)?));
+        }
         // can_id is node_id + protocol_id (same as function id)
         // can_ids are always <128
         // mask out lowest 7 bits to just get the protocol_id
@@ -1136,12 +1910,16 @@ impl Conn {
         let node_id = id & 0x007F;
         let p = match protocol_id {
             0x000 => Message::Nmt(Nmt::decode(frame)?),
-            0x080 if node_id == 0 => Message::Sync(Sync::decode(frame)?),
-            0x080 => Message::Emergency(Emergency::decode(frame)?),
+            // 0x080 itself is only a SYNC if it's the configured COB-ID, see above
+            0x080 if node_id != 0 => Message::Emergency(Emergency::decode(frame)?),
+            0x100 if node_id == 0 => Message::Time(TimeOfDay::decode(frame)?),
             0x180..=0x500 => Message::Pdo(Pdo::decode(frame)?),
             0x580..=0x600 => Message::Sdo(Sdo::decode(frame)?),
             0x700 => Message::Guard(Guard::decode(frame)?),
-            _ => todo!(),
+            0x780 if id == lss::LSS_MASTER_COB_ID || id == lss::LSS_SLAVE_COB_ID => {
+                Message::Lss(lss::Lss::decode(frame)?)
+            }
+            _ => return Err(CanOpenError::UnknownFrameRWType(id as u32)),
         };
         Ok(p)
     }
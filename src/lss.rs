@@ -0,0 +1,867 @@
+//! Layer Setting Services (CiA 305)
+//! LSS lets a master configure the node id and bit rate of devices over the bus,
+//! most commonly of devices shipping unconfigured (node id 0xFF).
+//! Devices are addressed by their identity (0x1018: vendor id, product code, revision number, serial number),
+//! so LSS works even when the node id is unknown.
+//! The master sends on COB-ID 0x7E5, slaves answer on 0x7E4.
+//!
+//! ```no_run
+//! # use canopeners::{Conn, lss::{LssIdentity, LssMaster, LssMode}};
+//! let conn = Conn::new("vcan0").unwrap();
+//! let lss = LssMaster::new(&conn);
+//! lss.switch_state_selective(&LssIdentity {
+//!     vendor_id: 0x1234,
+//!     product_code: 1,
+//!     revision_number: 2,
+//!     serial_number: 3,
+//! })
+//! .unwrap();
+//! lss.configure_node_id(0x10).unwrap();
+//! lss.store_configuration().unwrap();
+//! lss.switch_state_global(LssMode::Waiting).unwrap();
+//! ```
+//!
+//! When the identities aren't known, `LssMaster::fastscan` finds all unconfigured slaves
+//! and hands out node ids:
+//! ```no_run
+//! # use canopeners::{Conn, lss::LssMaster};
+//! # use std::time::Duration;
+//! let conn = Conn::new("vcan0").unwrap();
+//! let lss = LssMaster::new(&conn).with_timeout(Duration::from_millis(20));
+//! for slave in lss.fastscan(0x20..=0x2F).unwrap() {
+//!     println!("{:x?} is now node {}", slave.identity, slave.node_id);
+//! }
+//! ```
+//!
+//! `LssSlave` is the other side, for devices (or simulated ones) answering LSS requests.
+
+use std::time::{Duration, Instant};
+
+use socketcan::{EmbeddedFrame, Frame};
+
+use crate::{id_as_raw_std, u16_as_id, CanOpenError, Conn, FrameRW, Message, NmtFunction};
+
+pub const LSS_MASTER_COB_ID: u16 = 0x7E5;
+pub const LSS_SLAVE_COB_ID: u16 = 0x7E4;
+
+/// Node id of a device that has none yet
+pub const UNCONFIGURED_NODE_ID: u8 = 0xFF;
+
+/// How long `LssMaster` waits for a slave to answer by default
+pub const DEFAULT_LSS_TIMEOUT: Duration = Duration::from_millis(1000);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LssMode {
+    Waiting = 0,
+    Configuration = 1,
+}
+
+impl TryFrom<u8> for LssMode {
+    type Error = CanOpenError;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(LssMode::Waiting),
+            1 => Ok(LssMode::Configuration),
+            _ => Err(CanOpenError::ParseError(format!(
+                "{value:x} not a valid LSS mode"
+            ))),
+        }
+    }
+}
+
+/// One of the four parts of the LSS address (0x1018 sub-index 1..=4)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LssIdentityField {
+    VendorId = 0,
+    ProductCode = 1,
+    RevisionNumber = 2,
+    SerialNumber = 3,
+}
+
+impl LssIdentityField {
+    fn from_offset(offset: u8) -> Option<Self> {
+        match offset {
+            0 => Some(Self::VendorId),
+            1 => Some(Self::ProductCode),
+            2 => Some(Self::RevisionNumber),
+            3 => Some(Self::SerialNumber),
+            _ => None,
+        }
+    }
+}
+
+/// What `Lss::Inquire` asks for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LssInquiry {
+    VendorId = 0,
+    ProductCode = 1,
+    RevisionNumber = 2,
+    SerialNumber = 3,
+    NodeId = 4,
+}
+
+impl LssInquiry {
+    fn from_offset(offset: u8) -> Option<Self> {
+        match offset {
+            0 => Some(Self::VendorId),
+            1 => Some(Self::ProductCode),
+            2 => Some(Self::RevisionNumber),
+            3 => Some(Self::SerialNumber),
+            4 => Some(Self::NodeId),
+            _ => None,
+        }
+    }
+}
+
+/// Bit rates from the standard CiA 305 bit timing table (table selector 0)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LssBitRate {
+    Kbit1000 = 0,
+    Kbit800 = 1,
+    Kbit500 = 2,
+    Kbit250 = 3,
+    Kbit125 = 4,
+    Kbit50 = 6,
+    Kbit20 = 7,
+    Kbit10 = 8,
+    Auto = 9,
+}
+
+/// LSS address of a device, same as its identity object (0x1018)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LssIdentity {
+    pub vendor_id: u32,
+    pub product_code: u32,
+    pub revision_number: u32,
+    pub serial_number: u32,
+}
+
+impl LssIdentity {
+    pub fn get(&self, field: LssIdentityField) -> u32 {
+        match field {
+            LssIdentityField::VendorId => self.vendor_id,
+            LssIdentityField::ProductCode => self.product_code,
+            LssIdentityField::RevisionNumber => self.revision_number,
+            LssIdentityField::SerialNumber => self.serial_number,
+        }
+    }
+
+    pub fn set(&mut self, field: LssIdentityField, value: u32) {
+        match field {
+            LssIdentityField::VendorId => self.vendor_id = value,
+            LssIdentityField::ProductCode => self.product_code = value,
+            LssIdentityField::RevisionNumber => self.revision_number = value,
+            LssIdentityField::SerialNumber => self.serial_number = value,
+        }
+    }
+}
+
+/// Outcome of a configure/store command, as reported by the slave
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LssConfigResult {
+    /// 0 on success, 1..=254 are command specific, 255 means `spec_error` holds a manufacturer error
+    pub error_code: u8,
+    pub spec_error: u8,
+}
+
+impl LssConfigResult {
+    pub const OK: Self = Self {
+        error_code: 0,
+        spec_error: 0,
+    };
+
+    pub fn is_ok(&self) -> bool {
+        self.error_code == 0
+    }
+}
+
+/// LSS message. Variants ending in `Response` are sent by slaves, everything else by the master.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Lss {
+    SwitchStateGlobal(LssMode),
+    /// The master sends all four fields in order, the slave matching all of them responds
+    SwitchStateSelective(LssIdentityField, u32),
+    SwitchStateSelectiveResponse,
+    ConfigureNodeId(u8),
+    ConfigureNodeIdResponse(LssConfigResult),
+    ConfigureBitTiming {
+        table_selector: u8,
+        table_index: u8,
+    },
+    ConfigureBitTimingResponse(LssConfigResult),
+    ActivateBitTiming {
+        switch_delay_ms: u16,
+    },
+    StoreConfiguration,
+    StoreConfigurationResponse(LssConfigResult),
+    Inquire(LssInquiry),
+    InquireResponse(LssInquiry, u32),
+    /// Unconfigured slaves in waiting state whose `lss_sub` field matches `id_number`
+    /// on all bits from `bit_checked` upwards answer with `IdentifySlaveResponse`.
+    /// `bit_checked` 0x80 resets the scan, all unconfigured slaves answer.
+    Fastscan {
+        id_number: u32,
+        bit_checked: u8,
+        lss_sub: u8,
+        lss_next: u8,
+    },
+    IdentifySlaveResponse,
+}
+
+impl Lss {
+    /// True for messages sent by slaves
+    pub fn is_response(&self) -> bool {
+        matches!(
+            self,
+            Lss::SwitchStateSelectiveResponse
+                | Lss::ConfigureNodeIdResponse(_)
+                | Lss::ConfigureBitTimingResponse(_)
+                | Lss::StoreConfigurationResponse(_)
+                | Lss::InquireResponse(_, _)
+                | Lss::IdentifySlaveResponse
+        )
+    }
+
+    fn to_bytes(&self) -> [u8; 8] {
+        let mut data = [0u8; 8];
+        match self {
+            Lss::SwitchStateGlobal(mode) => {
+                data[0] = 0x04;
+                data[1] = *mode as u8;
+            }
+            Lss::SwitchStateSelective(field, value) => {
+                data[0] = 0x40 + *field as u8;
+                data[1..5].copy_from_slice(&value.to_le_bytes());
+            }
+            Lss::SwitchStateSelectiveResponse => data[0] = 0x44,
+            Lss::ConfigureNodeId(node_id) => {
+                data[0] = 0x11;
+                data[1] = *node_id;
+            }
+            Lss::ConfigureBitTiming {
+                table_selector,
+                table_index,
+            } => {
+                data[0] = 0x13;
+                data[1] = *table_selector;
+                data[2] = *table_index;
+            }
+            Lss::ActivateBitTiming { switch_delay_ms } => {
+                data[0] = 0x15;
+                data[1..3].copy_from_slice(&switch_delay_ms.to_le_bytes());
+            }
+            Lss::StoreConfiguration => data[0] = 0x17,
+            Lss::ConfigureNodeIdResponse(result) => {
+                data[0..3].copy_from_slice(&[0x11, result.error_code, result.spec_error])
+            }
+            Lss::ConfigureBitTimingResponse(result) => {
+                data[0..3].copy_from_slice(&[0x13, result.error_code, result.spec_error])
+            }
+            Lss::StoreConfigurationResponse(result) => {
+                data[0..3].copy_from_slice(&[0x17, result.error_code, result.spec_error])
+            }
+            Lss::Inquire(inquiry) => data[0] = 0x5A + *inquiry as u8,
+            Lss::InquireResponse(inquiry, value) => {
+                data[0] = 0x5A + *inquiry as u8;
+                data[1..5].copy_from_slice(&value.to_le_bytes());
+            }
+            Lss::Fastscan {
+                id_number,
+                bit_checked,
+                lss_sub,
+                lss_next,
+            } => {
+                data[0] = 0x51;
+                data[1..5].copy_from_slice(&id_number.to_le_bytes());
+                data[5] = *bit_checked;
+                data[6] = *lss_sub;
+                data[7] = *lss_next;
+            }
+            Lss::IdentifySlaveResponse => data[0] = 0x4F,
+        }
+        data
+    }
+
+    fn from_request(data: &[u8; 8]) -> Result<Self, CanOpenError> {
+        let value = u32::from_le_bytes(data[1..5].try_into().unwrap());
+        let lss = match data[0] {
+            0x04 => Lss::SwitchStateGlobal(data[1].try_into()?),
+            cs @ 0x40..=0x43 => {
+                Lss::SwitchStateSelective(LssIdentityField::from_offset(cs - 0x40).unwrap(), value)
+            }
+            0x11 => Lss::ConfigureNodeId(data[1]),
+            0x13 => Lss::ConfigureBitTiming {
+                table_selector: data[1],
+                table_index: data[2],
+            },
+            0x15 => Lss::ActivateBitTiming {
+                switch_delay_ms: u16::from_le_bytes([data[1], data[2]]),
+            },
+            0x17 => Lss::StoreConfiguration,
+            cs @ 0x5A..=0x5E => Lss::Inquire(LssInquiry::from_offset(cs - 0x5A).unwrap()),
+            0x51 => Lss::Fastscan {
+                id_number: value,
+                bit_checked: data[5],
+                lss_sub: data[6],
+                lss_next: data[7],
+            },
+            cs => {
+                return Err(CanOpenError::ParseError(format!(
+                    "unknown LSS command specifier: {cs:#x}"
+                )))
+            }
+        };
+        Ok(lss)
+    }
+
+    fn from_response(data: &[u8; 8]) -> Result<Self, CanOpenError> {
+        let value = u32::from_le_bytes(data[1..5].try_into().unwrap());
+        let result = LssConfigResult {
+            error_code: data[1],
+            spec_error: data[2],
+        };
+        let lss = match data[0] {
+            0x44 => Lss::SwitchStateSelectiveResponse,
+            0x4F => Lss::IdentifySlaveResponse,
+            0x11 => Lss::ConfigureNodeIdResponse(result),
+            0x13 => Lss::ConfigureBitTimingResponse(result),
+            0x17 => Lss::StoreConfigurationResponse(result),
+            cs @ 0x5A..=0x5E => {
+                Lss::InquireResponse(LssInquiry::from_offset(cs - 0x5A).unwrap(), value)
+            }
+            cs => {
+                return Err(CanOpenError::ParseError(format!(
+                    "unknown LSS response command specifier: {cs:#x}"
+                )))
+            }
+        };
+        Ok(lss)
+    }
+}
+
+impl FrameRW for Lss {
+    fn decode(frame: &socketcan::CanFrame) -> Result<Lss, CanOpenError> {
+        let id = id_as_raw_std(frame)?;
+        let data: [u8; 8] = frame.data().try_into().map_err(|_| {
+            CanOpenError::ParseError(format!(
+                "LSS messages are 8 bytes long, got {}",
+                frame.data().len()
+            ))
+        })?;
+        match id {
+            LSS_MASTER_COB_ID => Lss::from_request(&data),
+            LSS_SLAVE_COB_ID => Lss::from_response(&data),
+            _ => Err(CanOpenError::BadMessage(format!("not an LSS cob-id: {id}"))),
+        }
+    }
+
+    fn encode(&self, frame: &mut socketcan::CanFrame) {
+        let id = if self.is_response() {
+            LSS_SLAVE_COB_ID
+        } else {
+            LSS_MASTER_COB_ID
+        };
+        frame.set_id(u16_as_id(id));
+        frame.set_data(&self.to_bytes()).unwrap();
+    }
+}
+
+/// A slave found by `LssMaster::fastscan`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiscoveredSlave {
+    pub identity: LssIdentity,
+    /// Pending node id assigned during the scan, active after the next NMT reset communication
+    pub node_id: u8,
+}
+
+/// Porcelain for configuring devices over LSS
+/// Commands which are confirmed by the slave fail with `CanOpenError::Timeout`
+/// when no slave answers in time.
+#[derive(Debug)]
+pub struct LssMaster<'a> {
+    conn: &'a Conn,
+    timeout: Duration,
+}
+
+impl<'a> LssMaster<'a> {
+    pub fn new(conn: &'a Conn) -> Self {
+        Self {
+            conn,
+            timeout: DEFAULT_LSS_TIMEOUT,
+        }
+    }
+
+    /// How long to wait for a slave to answer
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn send(&self, lss: Lss) -> Result<(), CanOpenError> {
+        self.conn.send(&Message::Lss(lss))
+    }
+
+    /// Sends `request`, then waits for the first response `matches` accepts
+    fn request<T>(
+        &self,
+        request: Lss,
+        mut matches: impl FnMut(Lss) -> Option<T>,
+    ) -> Result<T, CanOpenError> {
+        self.send(request)?;
+        self.conn
+            .recv_matching(Instant::now() + self.timeout, |message| match message {
+                Message::Lss(lss) => matches(lss),
+                _ => None,
+            })?
+            .ok_or(CanOpenError::Timeout(self.timeout.as_millis() as u64))
+    }
+
+    fn configure(
+        &self,
+        command: &'static str,
+        request: Lss,
+        matches: impl Fn(&Lss) -> Option<LssConfigResult>,
+    ) -> Result<(), CanOpenError> {
+        let result = self.request(request, |lss| matches(&lss))?;
+        if result.is_ok() {
+            Ok(())
+        } else {
+            Err(CanOpenError::LssConfigurationFailed {
+                command,
+                error_code: result.error_code,
+                spec_error: result.spec_error,
+            })
+        }
+    }
+
+    /// Switches all slaves into `mode`. Not confirmed by the slaves.
+    pub fn switch_state_global(&self, mode: LssMode) -> Result<(), CanOpenError> {
+        self.send(Lss::SwitchStateGlobal(mode))
+    }
+
+    /// Switches the one slave with `identity` into configuration mode
+    pub fn switch_state_selective(&self, identity: &LssIdentity) -> Result<(), CanOpenError> {
+        for field in [
+            LssIdentityField::VendorId,
+            LssIdentityField::ProductCode,
+            LssIdentityField::RevisionNumber,
+        ] {
+            self.send(Lss::SwitchStateSelective(field, identity.get(field)))?;
+        }
+        self.request(
+            Lss::SwitchStateSelective(LssIdentityField::SerialNumber, identity.serial_number),
+            |lss| (lss == Lss::SwitchStateSelectiveResponse).then_some(()),
+        )
+    }
+
+    /// Sets the pending node id of the slave in configuration mode.
+    /// Valid ids are 1..=127, or 0xFF to make the slave unconfigured.
+    /// The new id becomes active after the next NMT reset communication.
+    pub fn configure_node_id(&self, node_id: u8) -> Result<(), CanOpenError> {
+        self.configure(
+            "configure node-id",
+            Lss::ConfigureNodeId(node_id),
+            |lss| match lss {
+                Lss::ConfigureNodeIdResponse(result) => Some(*result),
+                _ => None,
+            },
+        )
+    }
+
+    /// Sets the pending bit rate of the slave in configuration mode,
+    /// see `activate_bit_timing` for switching to it
+    pub fn configure_bit_timing(&self, bit_rate: LssBitRate) -> Result<(), CanOpenError> {
+        self.configure(
+            "configure bit timing",
+            Lss::ConfigureBitTiming {
+                table_selector: 0,
+                table_index: bit_rate as u8,
+            },
+            |lss| match lss {
+                Lss::ConfigureBitTimingResponse(result) => Some(*result),
+                _ => None,
+            },
+        )
+    }
+
+    /// Makes all slaves in configuration mode switch to their pending bit rate.
+    /// Slaves stop transmitting for `switch_delay`, switch, then wait another `switch_delay`
+    /// before transmitting again. Not confirmed by the slaves.
+    pub fn activate_bit_timing(&self, switch_delay: Duration) -> Result<(), CanOpenError> {
+        let switch_delay_ms = switch_delay
+            .as_millis()
+            .try_into()
+            .map_err(|e: std::num::TryFromIntError| CanOpenError::OverflowError(e.to_string()))?;
+        self.send(Lss::ActivateBitTiming { switch_delay_ms })
+    }
+
+    /// Makes the slave in configuration mode persist its pending node id and bit rate
+    pub fn store_configuration(&self) -> Result<(), CanOpenError> {
+        self.configure(
+            "store configuration",
+            Lss::StoreConfiguration,
+            |lss| match lss {
+                Lss::StoreConfigurationResponse(result) => Some(*result),
+                _ => None,
+            },
+        )
+    }
+
+    fn inquire(&self, inquiry: LssInquiry) -> Result<u32, CanOpenError> {
+        self.request(Lss::Inquire(inquiry), |lss| match lss {
+            Lss::InquireResponse(i, value) if i == inquiry => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Reads the identity of the slave in configuration mode
+    pub fn inquire_identity(&self) -> Result<LssIdentity, CanOpenError> {
+        Ok(LssIdentity {
+            vendor_id: self.inquire(LssInquiry::VendorId)?,
+            product_code: self.inquire(LssInquiry::ProductCode)?,
+            revision_number: self.inquire(LssInquiry::RevisionNumber)?,
+            serial_number: self.inquire(LssInquiry::SerialNumber)?,
+        })
+    }
+
+    /// Reads the active node id of the slave in configuration mode, 0xFF if unconfigured
+    pub fn inquire_node_id(&self) -> Result<u8, CanOpenError> {
+        Ok(self.inquire(LssInquiry::NodeId)? as u8)
+    }
+
+    /// Sends one fastscan request, then listens for the whole timeout.
+    /// Several slaves may answer the same request, so we can't stop at the first answer:
+    /// a late one would be mistaken for an answer to the next request.
+    fn fastscan_step(
+        &self,
+        id_number: u32,
+        bit_checked: u8,
+        lss_sub: u8,
+        lss_next: u8,
+    ) -> Result<bool, CanOpenError> {
+        self.send(Lss::Fastscan {
+            id_number,
+            bit_checked,
+            lss_sub,
+            lss_next,
+        })?;
+        let mut answered = false;
+        self.conn
+            .recv_matching(Instant::now() + self.timeout, |message| {
+                if let Message::Lss(Lss::IdentifySlaveResponse) = message {
+                    answered = true;
+                }
+                None::<()>
+            })?;
+        Ok(answered)
+    }
+
+    /// Bisects the identity of one unconfigured slave, bit by bit.
+    /// On success the slave is left in configuration mode.
+    /// Returns `None` if the slave stopped answering.
+    fn fastscan_identify(&self) -> Result<Option<LssIdentity>, CanOpenError> {
+        let mut identity = LssIdentity::default();
+        for lss_sub in 0..4 {
+            let mut value = 0u32;
+            for bit in (0..32).rev() {
+                // slaves whose bits above `bit` match `value` and have `bit` cleared answer,
+                // if nobody does the bit must be set
+                if !self.fastscan_step(value, bit, lss_sub, lss_sub)? {
+                    value |= 1 << bit;
+                }
+            }
+            // confirm the whole field, which moves the matching slave on to the next one.
+            // After the serial number, the slave switches to configuration mode.
+            if !self.fastscan_step(value, 0, lss_sub, (lss_sub + 1) % 4)? {
+                return Ok(None);
+            }
+            identity.set(LssIdentityField::from_offset(lss_sub).unwrap(), value);
+        }
+        Ok(Some(identity))
+    }
+
+    /// Finds every unconfigured slave (node id 0xFF) and assigns it the next node id from `node_ids`.
+    /// Slaves left once `node_ids` runs out stay unconfigured.
+    /// Each slave takes 132 request/timeout rounds to identify, so keep the timeout short (see `with_timeout`).
+    pub fn fastscan(
+        &self,
+        node_ids: impl IntoIterator<Item = u8>,
+    ) -> Result<Vec<DiscoveredSlave>, CanOpenError> {
+        let mut node_ids = node_ids.into_iter().peekable();
+        let mut discovered = Vec::new();
+        // make sure no slave is left in configuration mode, those would answer selective commands
+        self.switch_state_global(LssMode::Waiting)?;
+        while node_ids.peek().is_some() && self.fastscan_step(0, 0x80, 0, 0)? {
+            let identity = self.fastscan_identify()?.ok_or_else(|| {
+                CanOpenError::ConnectionError(
+                    "LSS slave stopped answering during fastscan".to_owned(),
+                )
+            })?;
+            let node_id = node_ids.next().unwrap();
+            self.configure_node_id(node_id)?;
+            // with a pending node id, the slave no longer takes part in fastscan
+            self.switch_state_global(LssMode::Waiting)?;
+            discovered.push(DiscoveredSlave { identity, node_id });
+        }
+        Ok(discovered)
+    }
+}
+
+/// Bit timing, as sent in `Lss::ConfigureBitTiming`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LssBitTiming {
+    pub table_selector: u8,
+    pub table_index: u8,
+}
+
+/// What an `LssSlave` persists on "store configuration"
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LssStoredConfig {
+    pub node_id: u8,
+    pub bit_timing: Option<LssBitTiming>,
+}
+
+/// Non-volatile storage of an `LssSlave`, eg. a file or flash page
+pub trait LssStorage {
+    fn store(&mut self, config: &LssStoredConfig) -> std::io::Result<()>;
+}
+
+/// LSS responder for a device.
+/// Feed it every received message, send back whatever it returns.
+/// A device without a node id (`UNCONFIGURED_NODE_ID`) should only talk LSS until `node_id` returns `Some`.
+pub struct LssSlave {
+    identity: LssIdentity,
+    mode: LssMode,
+    active_node_id: u8,
+    pending_node_id: u8,
+    pending_bit_timing: Option<LssBitTiming>,
+    activated_bit_timing: Option<(LssBitTiming, Duration)>,
+    selective_matched: u8,
+    fastscan_pos: u8,
+    storage: Option<Box<dyn LssStorage + Send>>,
+}
+
+impl std::fmt::Debug for LssSlave {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LssSlave")
+            .field("identity", &self.identity)
+            .field("mode", &self.mode)
+            .field("active_node_id", &self.active_node_id)
+            .field("pending_node_id", &self.pending_node_id)
+            .field("pending_bit_timing", &self.pending_bit_timing)
+            .field("storage", &self.storage.is_some())
+            .finish()
+    }
+}
+
+impl LssSlave {
+    /// `identity` is the device's 0x1018 object, `node_id` is the stored one
+    /// (`UNCONFIGURED_NODE_ID` if there is none)
+    pub fn new(identity: LssIdentity, node_id: u8) -> Self {
+        Self {
+            identity,
+            mode: LssMode::Waiting,
+            active_node_id: node_id,
+            pending_node_id: node_id,
+            pending_bit_timing: None,
+            activated_bit_timing: None,
+            selective_matched: 0,
+            fastscan_pos: 0,
+            storage: None,
+        }
+    }
+
+    /// Without storage, "store configuration" is answered with "not supported"
+    pub fn with_storage(mut self, storage: impl LssStorage + Send + 'static) -> Self {
+        self.storage = Some(Box::new(storage));
+        self
+    }
+
+    pub fn identity(&self) -> &LssIdentity {
+        &self.identity
+    }
+
+    pub fn mode(&self) -> LssMode {
+        self.mode
+    }
+
+    /// Active node id, `None` while unconfigured
+    pub fn node_id(&self) -> Option<u8> {
+        (self.active_node_id != UNCONFIGURED_NODE_ID).then_some(self.active_node_id)
+    }
+
+    /// Node id that becomes active on the next NMT reset communication
+    pub fn pending_node_id(&self) -> u8 {
+        self.pending_node_id
+    }
+
+    pub fn pending_bit_timing(&self) -> Option<LssBitTiming> {
+        self.pending_bit_timing
+    }
+
+    /// Set once the master activated the pending bit timing.
+    /// The device should go silent for the switch delay, switch, then wait another switch delay.
+    pub fn take_bit_timing_activation(&mut self) -> Option<(LssBitTiming, Duration)> {
+        self.activated_bit_timing.take()
+    }
+
+    /// Handles LSS requests and NMT resets, everything else is ignored.
+    /// Returns the response to send, if any.
+    pub fn process(&mut self, message: &Message) -> Option<Message> {
+        match message {
+            Message::Lss(lss) => self.handle(lss).map(Message::Lss),
+            Message::Nmt(nmt)
+                if nmt.target_node == 0
+                    || (self.node_id().is_some() && nmt.target_node == self.active_node_id) =>
+            {
+                if let NmtFunction::ResetCommunication | NmtFunction::ResetNode = nmt.function {
+                    self.active_node_id = self.pending_node_id;
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Handles one LSS request, returns the response to send, if any
+    pub fn handle(&mut self, lss: &Lss) -> Option<Lss> {
+        let configuring = self.mode == LssMode::Configuration;
+        match *lss {
+            Lss::SwitchStateGlobal(mode) => {
+                self.mode = mode;
+                // a device configured for the first time starts using its node id right away,
+                // as if it got an NMT reset communication
+                if mode == LssMode::Waiting
+                    && self.active_node_id == UNCONFIGURED_NODE_ID
+                    && self.pending_node_id != UNCONFIGURED_NODE_ID
+                {
+                    self.active_node_id = self.pending_node_id;
+                }
+                None
+            }
+            Lss::SwitchStateSelective(field, value) => {
+                self.selective_matched =
+                    if field as u8 == self.selective_matched && self.identity.get(field) == value {
+                        self.selective_matched + 1
+                    } else {
+                        0
+                    };
+                if self.selective_matched == 4 {
+                    self.selective_matched = 0;
+                    self.mode = LssMode::Configuration;
+                    Some(Lss::SwitchStateSelectiveResponse)
+                } else {
+                    None
+                }
+            }
+            Lss::ConfigureNodeId(node_id) if configuring => {
+                let result = if (1..=127).contains(&node_id) || node_id == UNCONFIGURED_NODE_ID {
+                    self.pending_node_id = node_id;
+                    LssConfigResult::OK
+                } else {
+                    // node id out of range
+                    LssConfigResult {
+                        error_code: 1,
+                        spec_error: 0,
+                    }
+                };
+                Some(Lss::ConfigureNodeIdResponse(result))
+            }
+            Lss::ConfigureBitTiming {
+                table_selector,
+                table_index,
+            } if configuring => {
+                let result = if table_selector == 0 && table_index <= 9 && table_index != 5 {
+                    self.pending_bit_timing = Some(LssBitTiming {
+                        table_selector,
+                        table_index,
+                    });
+                    LssConfigResult::OK
+                } else {
+                    // bit timing not supported
+                    LssConfigResult {
+                        error_code: 1,
+                        spec_error: 0,
+                    }
+                };
+                Some(Lss::ConfigureBitTimingResponse(result))
+            }
+            Lss::ActivateBitTiming { switch_delay_ms } if configuring => {
+                if let Some(bit_timing) = self.pending_bit_timing {
+                    self.activated_bit_timing =
+                        Some((bit_timing, Duration::from_millis(switch_delay_ms as u64)));
+                }
+                None
+            }
+            Lss::StoreConfiguration if configuring => {
+                let config = LssStoredConfig {
+                    node_id: self.pending_node_id,
+                    bit_timing: self.pending_bit_timing,
+                };
+                let error_code = match &mut self.storage {
+                    // store configuration not supported
+                    None => 1,
+                    Some(storage) => match storage.store(&config) {
+                        Ok(()) => 0,
+                        // storage media access error
+                        Err(_) => 2,
+                    },
+                };
+                Some(Lss::StoreConfigurationResponse(LssConfigResult {
+                    error_code,
+                    spec_error: 0,
+                }))
+            }
+            Lss::Inquire(inquiry) if configuring => {
+                let value = match inquiry {
+                    LssInquiry::VendorId => self.identity.vendor_id,
+                    LssInquiry::ProductCode => self.identity.product_code,
+                    LssInquiry::RevisionNumber => self.identity.revision_number,
+                    LssInquiry::SerialNumber => self.identity.serial_number,
+                    LssInquiry::NodeId => self.active_node_id as u32,
+                };
+                Some(Lss::InquireResponse(inquiry, value))
+            }
+            Lss::Fastscan {
+                id_number,
+                bit_checked,
+                lss_sub,
+                lss_next,
+            } if !configuring
+                && self.active_node_id == UNCONFIGURED_NODE_ID
+                && self.pending_node_id == UNCONFIGURED_NODE_ID =>
+            {
+                self.fastscan(id_number, bit_checked, lss_sub, lss_next)
+            }
+            _ => None,
+        }
+    }
+
+    fn fastscan(
+        &mut self,
+        id_number: u32,
+        bit_checked: u8,
+        lss_sub: u8,
+        lss_next: u8,
+    ) -> Option<Lss> {
+        if bit_checked == 0x80 {
+            self.fastscan_pos = 0;
+            return Some(Lss::IdentifySlaveResponse);
+        }
+        let field = LssIdentityField::from_offset(lss_sub)?;
+        if bit_checked > 31
+            || lss_sub != self.fastscan_pos
+            || (self.identity.get(field) ^ id_number) >> bit_checked != 0
+        {
+            return None;
+        }
+        if bit_checked == 0 {
+            self.fastscan_pos = lss_next;
+            // wrapping around to an earlier field means the whole identity matched
+            if lss_next < lss_sub {
+                self.mode = LssMode::Configuration;
+            }
+        }
+        Some(Lss::IdentifySlaveResponse)
+    }
+}
@@ -0,0 +1,41 @@
+//! Reads and writes objects by their name in a device description
+//! ```no_run
+//! # use canopeners::{Conn, eds, named};
+//! let mut conn = Conn::new("vcan0").unwrap();
+//! let eds = eds::load("drive.eds").unwrap();
+//! let heartbeat = named::read_by_name(&mut conn, 5, &eds, "Producer Heartbeat Time").unwrap();
+//! named::write_by_name(&mut conn, 5, &eds, "Controlword", "0x0F").unwrap();
+//! // several records have a "COB-ID used by RPDO", say which one
+//! named::read_by_name(&mut conn, 5, &eds, "RPDO communication parameter 1/COB-ID used by RPDO").unwrap();
+//! ```
+//! Names are looked up with `od::ObjectDictionary::find`: misspelled names fail with
+//! suggestions, ambiguous ones with the qualified names to pick from.
+//! Values are converted from and to the declared data type like `od::ValueExpr` does,
+//! so integers are written as in an EDS (`0x0F`, `15`, `$NODEID+0x180`).
+
+use crate::od::{ObjectDictionary, ValueExpr};
+use crate::{CanOpenError, Conn};
+
+pub fn read_by_name(
+    conn: &mut Conn,
+    node_id: u8,
+    description: &ObjectDictionary,
+    name: &str,
+) -> Result<ValueExpr, CanOpenError> {
+    let variable = description.find(name)?;
+    let data = conn.sdo_read(node_id, variable.index, variable.sub_index)?;
+    ValueExpr::from_bytes(variable.data_type, &data)
+}
+
+/// Nothing is sent if `value` doesn't fit the declared data type
+pub fn write_by_name(
+    conn: &mut Conn,
+    node_id: u8,
+    description: &ObjectDictionary,
+    name: &str,
+    value: &str,
+) -> Result<(), CanOpenError> {
+    let variable = description.find(name)?;
+    let data = ValueExpr::new(value).to_bytes(variable.data_type, node_id)?;
+    conn.sdo_write(node_id, variable.index, variable.sub_index, &data)
+}
@@ -0,0 +1,465 @@
+//! Object dictionary model, as described by a device description file (see `eds`)
+//! Mostly describes the entries (name, type, access, defaults...).
+//! Configuration files (see `dcf`) also carry the actual values of one device.
+
+use std::collections::BTreeMap;
+
+use crate::enums::{AccessType, DataType, ObjectType};
+use crate::CanOpenError;
+
+/// Parses an integer the way device description files write them:
+/// decimal, hex with a 0x prefix, or octal with a leading 0
+pub(crate) fn parse_integer(s: &str) -> Option<i128> {
+    let s = s.trim();
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let value = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        i128::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i128::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
+
+/// A value as written in a device description file.
+/// Kept verbatim, since it may depend on the node id (eg. `$NODEID+0x180`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValueExpr(String);
+
+impl ValueExpr {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_node_id_relative(&self) -> bool {
+        self.0.to_ascii_uppercase().contains("$NODEID")
+    }
+
+    /// Evaluates sums of integers and `$NODEID`, eg. `$NODEID+0x600` or `0x80+$NODEID`
+    pub fn to_integer(&self, node_id: u8) -> Result<i128, CanOpenError> {
+        self.0
+            .split('+')
+            .map(|term| {
+                let term = term.trim();
+                if term.eq_ignore_ascii_case("$NODEID") {
+                    Some(node_id as i128)
+                } else {
+                    parse_integer(term)
+                }
+            })
+            .sum::<Option<i128>>()
+            .ok_or_else(|| CanOpenError::ParseError(format!("{:?} is not an integer", self.0)))
+    }
+
+    /// Encodes the value the way it goes over SDO
+    pub fn to_bytes(&self, data_type: DataType, node_id: u8) -> Result<Vec<u8>, CanOpenError> {
+        let parse_error =
+            || CanOpenError::ParseError(format!("{:?} is not a {data_type:?}", self.0));
+        match data_type {
+            DataType::VisibleString => Ok(self.0.as_bytes().to_vec()),
+            DataType::UnicodeString => {
+                Ok(self.0.encode_utf16().flat_map(u16::to_le_bytes).collect())
+            }
+            // hex digits, optionally separated by whitespace
+            DataType::OctetString | DataType::Domain => {
+                let digits: Vec<char> = self.0.chars().filter(|c| !c.is_whitespace()).collect();
+                if !digits.len().is_multiple_of(2) {
+                    return Err(parse_error());
+                }
+                digits
+                    .chunks(2)
+                    .map(|pair| {
+                        let hi = pair[0].to_digit(16)?;
+                        let lo = pair[1].to_digit(16)?;
+                        Some((hi << 4 | lo) as u8)
+                    })
+                    .collect::<Option<_>>()
+                    .ok_or_else(parse_error)
+            }
+            DataType::Real32 => Ok(self
+                .0
+                .trim()
+                .parse::<f32>()
+                .map_err(|_| parse_error())?
+                .to_le_bytes()
+                .to_vec()),
+            DataType::Real64 => Ok(self
+                .0
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| parse_error())?
+                .to_le_bytes()
+                .to_vec()),
+            _ => {
+                let size = data_type.size().ok_or_else(|| {
+                    CanOpenError::NotYetImplemented(format!("values of type {data_type:?}"))
+                })?;
+                let value = self.to_integer(node_id)?;
+                let bits = 8 * size as u32;
+                let (min, max) = if data_type.is_signed_integer() {
+                    (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+                } else if data_type == DataType::Boolean {
+                    (0, 1)
+                } else {
+                    (0, (1i128 << bits) - 1)
+                };
+                if value < min || value > max {
+                    return Err(CanOpenError::OverflowError(format!(
+                        "{value} doesn't fit a {data_type:?}"
+                    )));
+                }
+                Ok(value.to_le_bytes()[..size].to_vec())
+            }
+        }
+    }
+
+    /// Formats a value read over SDO: integers as hex (unsigned) or decimal (signed),
+    /// strings as text and octet strings as hex digits
+    pub fn from_bytes(data_type: DataType, data: &[u8]) -> Result<Self, CanOpenError> {
+        let length_error = || {
+            CanOpenError::ParseError(format!("{} bytes is not a valid {data_type:?}", data.len()))
+        };
+        let value = match data_type {
+            // strings may be padded with NULs
+            DataType::VisibleString => String::from_utf8_lossy(data)
+                .trim_end_matches('\0')
+                .to_owned(),
+            DataType::UnicodeString => {
+                if !data.len().is_multiple_of(2) {
+                    return Err(length_error());
+                }
+                let units: Vec<u16> = data
+                    .chunks(2)
+                    .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+                    .trim_end_matches('\0')
+                    .to_owned()
+            }
+            DataType::OctetString | DataType::Domain => {
+                data.iter().map(|b| format!("{b:02X}")).collect()
+            }
+            DataType::Real32 => {
+                f32::from_le_bytes(data.try_into().map_err(|_| length_error())?).to_string()
+            }
+            DataType::Real64 => {
+                f64::from_le_bytes(data.try_into().map_err(|_| length_error())?).to_string()
+            }
+            _ => {
+                let size = data_type.size().ok_or_else(|| {
+                    CanOpenError::NotYetImplemented(format!("values of type {data_type:?}"))
+                })?;
+                if data.len() != size {
+                    return Err(length_error());
+                }
+                let negative = data_type.is_signed_integer() && data[size - 1] & 0x80 != 0;
+                let mut bytes = [if negative { 0xFF } else { 0 }; 16];
+                bytes[..size].copy_from_slice(data);
+                let value = i128::from_le_bytes(bytes);
+                if data_type.is_signed_integer() || data_type == DataType::Boolean {
+                    value.to_string()
+                } else {
+                    format!("{value:#X}")
+                }
+            }
+        };
+        Ok(Self(value))
+    }
+}
+
+/// A single value in the object dictionary: a VAR object, or one sub-index of an array/record
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Variable {
+    pub index: u16,
+    pub sub_index: u8,
+    pub name: String,
+    pub data_type: DataType,
+    pub access_type: AccessType,
+    pub default_value: Option<ValueExpr>,
+    /// Whether the value may be mapped into a PDO
+    pub pdo_mapping: bool,
+    pub low_limit: Option<ValueExpr>,
+    pub high_limit: Option<ValueExpr>,
+    /// Actual value on a configured device, only found in DCFs
+    pub parameter_value: Option<ValueExpr>,
+}
+
+impl Variable {
+    pub fn new(
+        index: u16,
+        sub_index: u8,
+        name: impl Into<String>,
+        data_type: DataType,
+        access_type: AccessType,
+    ) -> Self {
+        Self {
+            index,
+            sub_index,
+            name: name.into(),
+            data_type,
+            access_type,
+            default_value: None,
+            pdo_mapping: false,
+            low_limit: None,
+            high_limit: None,
+            parameter_value: None,
+        }
+    }
+
+    /// The configured value if there is one, the default otherwise
+    pub fn value(&self) -> Option<&ValueExpr> {
+        self.parameter_value
+            .as_ref()
+            .or(self.default_value.as_ref())
+    }
+
+    /// "0x1601sub1", hex index and decimal sub-index like in error messages.
+    /// Unlike qualified names, always unique.
+    pub fn index_name(&self) -> String {
+        format!("{:#06x}sub{}", self.index, self.sub_index)
+    }
+}
+
+/// One index of the object dictionary
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Object {
+    pub index: u16,
+    pub name: String,
+    pub object_type: ObjectType,
+    /// For VAR and DOMAIN objects, the object itself at sub-index 0
+    pub sub_objects: BTreeMap<u8, Variable>,
+}
+
+impl Object {
+    pub fn new(index: u16, name: impl Into<String>, object_type: ObjectType) -> Self {
+        Self {
+            index,
+            name: name.into(),
+            object_type,
+            sub_objects: BTreeMap::new(),
+        }
+    }
+
+    pub fn sub_object(&self, sub_index: u8) -> Option<&Variable> {
+        self.sub_objects.get(&sub_index)
+    }
+
+    pub fn insert(&mut self, variable: Variable) -> Option<Variable> {
+        self.sub_objects.insert(variable.sub_index, variable)
+    }
+}
+
+/// `[FileInfo]` section, describes the description file itself
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileInfo {
+    pub file_name: String,
+    pub file_version: String,
+    pub file_revision: String,
+    pub eds_version: String,
+    pub description: String,
+    pub creation_time: String,
+    pub creation_date: String,
+    pub created_by: String,
+    pub modification_time: String,
+    pub modification_date: String,
+    pub modified_by: String,
+}
+
+/// `[DeviceInfo]` section
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeviceInfo {
+    pub vendor_name: String,
+    pub vendor_number: Option<u32>,
+    pub product_name: String,
+    pub product_number: Option<u32>,
+    pub revision_number: Option<u32>,
+    pub order_code: String,
+    /// Supported bit rates in kbit/s
+    pub baud_rates: Vec<u16>,
+    pub simple_boot_up_master: bool,
+    pub simple_boot_up_slave: bool,
+    pub granularity: u8,
+    pub dynamic_channels_supported: u8,
+    pub group_messaging: bool,
+    pub nr_of_rx_pdo: u16,
+    pub nr_of_tx_pdo: u16,
+    pub lss_supported: bool,
+}
+
+/// `[DeviceComissioning]` section of a DCF (sic, that's how CiA 306 spells it).
+/// Says where on which network the configured device lives.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeviceCommissioning {
+    pub node_id: u8,
+    pub node_name: String,
+    /// in kbit/s
+    pub baudrate: u16,
+    pub net_number: u32,
+    pub network_name: String,
+    pub canopen_manager: bool,
+    pub lss_serial_number: Option<u32>,
+}
+
+/// Everything a device description says about a device
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObjectDictionary {
+    pub file_info: FileInfo,
+    pub device_info: DeviceInfo,
+    /// Only present in DCFs
+    pub device_commissioning: Option<DeviceCommissioning>,
+    objects: BTreeMap<u16, Object>,
+}
+
+impl ObjectDictionary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces (and returns) any object already at the same index
+    pub fn insert(&mut self, object: Object) -> Option<Object> {
+        self.objects.insert(object.index, object)
+    }
+
+    pub fn get(&self, index: u16) -> Option<&Object> {
+        self.objects.get(&index)
+    }
+
+    pub fn get_mut(&mut self, index: u16) -> Option<&mut Object> {
+        self.objects.get_mut(&index)
+    }
+
+    pub fn variable(&self, index: u16, sub_index: u8) -> Option<&Variable> {
+        self.get(index)?.sub_object(sub_index)
+    }
+
+    pub fn variable_mut(&mut self, index: u16, sub_index: u8) -> Option<&mut Variable> {
+        self.get_mut(index)?.sub_objects.get_mut(&sub_index)
+    }
+
+    /// Objects ordered by index
+    pub fn objects(&self) -> impl Iterator<Item = &Object> {
+        self.objects.values()
+    }
+
+    /// All variables, ordered by index and sub-index
+    pub fn variables(&self) -> impl Iterator<Item = &Variable> {
+        self.objects().flat_map(|o| o.sub_objects.values())
+    }
+
+    /// "Identity object/Vendor-ID" for sub-indices of arrays and records, the plain name otherwise
+    pub fn qualified_name(&self, variable: &Variable) -> String {
+        match self.get(variable.index) {
+            Some(object) if object.object_type.has_sub_objects() => {
+                format!("{}/{}", object.name, variable.name)
+            }
+            _ => variable.name.clone(),
+        }
+    }
+
+    /// The variable called `name`, or qualified like "Identity object/Vendor-ID",
+    /// or by index like "0x1018sub1" (see `Variable::index_name`, "0x1000" is sub-index 0).
+    /// Exact matches win over ones ignoring case and whitespace.
+    /// Fails with `AmbiguousName` if several variables match (eg. "Highest sub-index supported"),
+    /// and with `UnknownName`, listing similar names, if none do.
+    pub fn find(&self, name: &str) -> Result<&Variable, CanOpenError> {
+        if let Some((index, sub_index)) = parse_index_name(name) {
+            return self
+                .variable(index, sub_index)
+                .ok_or_else(|| CanOpenError::UnknownName {
+                    name: name.to_owned(),
+                    suggestions: Vec::new(),
+                });
+        }
+        let exact = |candidate: &str| candidate == name;
+        let normalized = normalize_name(name);
+        let loose = |candidate: &str| normalize_name(candidate) == normalized;
+        for matches_name in [&exact as &dyn Fn(&str) -> bool, &loose] {
+            let found: Vec<&Variable> = self
+                .variables()
+                .filter(|v| matches_name(&v.name) || matches_name(&self.qualified_name(v)))
+                .collect();
+            match found[..] {
+                [] => continue,
+                [variable] => return Ok(variable),
+                _ => {
+                    // eg. every 0x16xx has a "Receive PDO mapping/Mapping entry 1",
+                    // those can only be told apart by index
+                    let qualified: Vec<String> =
+                        found.iter().map(|v| self.qualified_name(v)).collect();
+                    let unique = |q: &String| qualified.iter().filter(|o| *o == q).count() == 1;
+                    let candidates = found
+                        .iter()
+                        .zip(&qualified)
+                        .map(|(v, q)| if unique(q) { q.clone() } else { v.index_name() })
+                        .collect();
+                    return Err(CanOpenError::AmbiguousName {
+                        name: name.to_owned(),
+                        candidates,
+                    });
+                }
+            }
+        }
+
+        // similar enough: a few typos, more for longer names
+        let max_distance = (normalized.chars().count() / 3).max(2);
+        let mut similar: Vec<(usize, String)> = self
+            .variables()
+            .filter_map(|v| {
+                let qualified = self.qualified_name(v);
+                let distance = levenshtein(&normalized, &normalize_name(&v.name))
+                    .min(levenshtein(&normalized, &normalize_name(&qualified)));
+                (distance <= max_distance).then_some((distance, qualified))
+            })
+            .collect();
+        similar.sort();
+        similar.dedup_by(|a, b| a.1 == b.1);
+        Err(CanOpenError::UnknownName {
+            name: name.to_owned(),
+            suggestions: similar.into_iter().take(5).map(|(_, n)| n).collect(),
+        })
+    }
+}
+
+/// "0x1601sub1" -> (0x1601, 1), "0x1018sub10" -> (0x1018, 10), "0x1000" -> (0x1000, 0)
+fn parse_index_name(name: &str) -> Option<(u16, u8)> {
+    let name = name.trim().to_lowercase();
+    let rest = name.strip_prefix("0x")?;
+    let (index, sub_index) = rest.split_once("sub").unwrap_or((rest, "0"));
+    Some((
+        u16::from_str_radix(index, 16).ok()?,
+        sub_index.parse().ok()?,
+    ))
+}
+
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Edits (insertions, deletions, substitutions) to get from `a` to `b`
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + (ca != *cb) as usize;
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
@@ -0,0 +1,172 @@
+//! PDO communication and mapping parameters (0x1400-0x1BFF)
+//! ```no_run
+//! # use canopeners::{Conn, pdo::{PdoConfig, PdoDirection, PdoMapping}};
+//! let mut conn = Conn::new("vcan0").unwrap();
+//! // TPDO1 of node 5: statusword and position actual value, every 10ms
+//! let config = PdoConfig {
+//!     cob_id: 0x185,
+//!     transmission_type: 0xFE,
+//!     inhibit_time: None,
+//!     event_timer: Some(10),
+//!     mappings: vec![PdoMapping::new(0x6041, 0, 16), PdoMapping::new(0x6064, 0, 32)],
+//! };
+//! config.write(&mut conn, 5, PdoDirection::Tx, 0).unwrap();
+//! assert_eq!(PdoConfig::read(&mut conn, 5, PdoDirection::Tx, 0).unwrap(), config);
+//! ```
+//! PDOs are numbered from 0 here, like the parameter indices: TPDO1 is `(PdoDirection::Tx, 0)`.
+
+use crate::{CanOpenError, Conn};
+
+/// COB-ID bit 31: the PDO doesn't exist or isn't in use
+pub const COB_ID_INVALID: u32 = 1 << 31;
+
+/// As seen from the node: RPDOs are received by it, TPDOs transmitted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PdoDirection {
+    Rx,
+    Tx,
+}
+
+impl PdoDirection {
+    fn communication_index(&self, pdo: u16) -> Result<u16, CanOpenError> {
+        Ok(match self {
+            PdoDirection::Rx => 0x1400,
+            PdoDirection::Tx => 0x1800,
+        } + pdo_offset(pdo)?)
+    }
+
+    fn mapping_index(&self, pdo: u16) -> Result<u16, CanOpenError> {
+        Ok(match self {
+            PdoDirection::Rx => 0x1600,
+            PdoDirection::Tx => 0x1A00,
+        } + pdo_offset(pdo)?)
+    }
+}
+
+fn pdo_offset(pdo: u16) -> Result<u16, CanOpenError> {
+    if pdo > 0x1FF {
+        return Err(CanOpenError::OverflowError(format!(
+            "PDO {pdo} is over 511"
+        )));
+    }
+    Ok(pdo)
+}
+
+/// One mapped object, encoded as index << 16 | sub-index << 8 | length in bits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PdoMapping {
+    pub index: u16,
+    pub sub_index: u8,
+    pub bits: u8,
+}
+
+impl PdoMapping {
+    pub fn new(index: u16, sub_index: u8, bits: u8) -> Self {
+        Self {
+            index,
+            sub_index,
+            bits,
+        }
+    }
+
+    pub fn encode(&self) -> u32 {
+        (self.index as u32) << 16 | (self.sub_index as u32) << 8 | self.bits as u32
+    }
+
+    pub fn decode(value: u32) -> Self {
+        Self {
+            index: (value >> 16) as u16,
+            sub_index: (value >> 8) as u8,
+            bits: value as u8,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PdoConfig {
+    /// sub1, bit 31 set means the PDO is disabled
+    pub cob_id: u32,
+    /// sub2, 0xFE/0xFF are event driven, 1..=240 sent every nth SYNC
+    pub transmission_type: u8,
+    /// sub3, in multiples of 100µs. None if the node doesn't have it.
+    pub inhibit_time: Option<u16>,
+    /// sub5, in ms. None if the node doesn't have it.
+    pub event_timer: Option<u16>,
+    pub mappings: Vec<PdoMapping>,
+}
+
+/// Optional sub-indices the node doesn't have are None
+fn optional(
+    conn: &mut Conn,
+    node_id: u8,
+    index: u16,
+    sub_index: u8,
+) -> Result<Option<u16>, CanOpenError> {
+    match conn.sdo_read_typed(node_id, index, sub_index) {
+        Ok(value) => Ok(Some(value)),
+        Err(CanOpenError::SdoAbortTransfer { .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+impl PdoConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.cob_id & COB_ID_INVALID == 0
+    }
+
+    /// Reads the communication and mapping parameters of one PDO
+    pub fn read(
+        conn: &mut Conn,
+        node_id: u8,
+        direction: PdoDirection,
+        pdo: u16,
+    ) -> Result<Self, CanOpenError> {
+        let communication = direction.communication_index(pdo)?;
+        let mapping = direction.mapping_index(pdo)?;
+        let count: u8 = conn.sdo_read_typed(node_id, mapping, 0)?;
+        let mappings = (1..=count)
+            .map(|sub_index| {
+                conn.sdo_read_typed(node_id, mapping, sub_index)
+                    .map(PdoMapping::decode)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            cob_id: conn.sdo_read_typed(node_id, communication, 1)?,
+            transmission_type: conn.sdo_read_typed(node_id, communication, 2)?,
+            inhibit_time: optional(conn, node_id, communication, 3)?,
+            event_timer: optional(conn, node_id, communication, 5)?,
+            mappings,
+        })
+    }
+
+    /// Reconfigures one PDO the way CiA 301 asks for: disable it, write the communication
+    /// parameters, clear the mapping, write the new one, then enable it (if `cob_id` says so).
+    /// `None` for the inhibit time or event timer leaves them as they are.
+    pub fn write(
+        &self,
+        conn: &mut Conn,
+        node_id: u8,
+        direction: PdoDirection,
+        pdo: u16,
+    ) -> Result<(), CanOpenError> {
+        let communication = direction.communication_index(pdo)?;
+        let mapping = direction.mapping_index(pdo)?;
+        let count: u8 = self.mappings.len().try_into().map_err(|_| {
+            CanOpenError::OverflowError(format!("{} PDO mappings", self.mappings.len()))
+        })?;
+        conn.sdo_write_typed(node_id, communication, 1, &(self.cob_id | COB_ID_INVALID))?;
+        conn.sdo_write_typed(node_id, communication, 2, &self.transmission_type)?;
+        if let Some(inhibit_time) = self.inhibit_time {
+            conn.sdo_write_typed(node_id, communication, 3, &inhibit_time)?;
+        }
+        if let Some(event_timer) = self.event_timer {
+            conn.sdo_write_typed(node_id, communication, 5, &event_timer)?;
+        }
+        conn.sdo_write_typed(node_id, mapping, 0, &0u8)?;
+        for (sub_index, m) in (1..).zip(&self.mappings) {
+            conn.sdo_write_typed(node_id, mapping, sub_index, &m.encode())?;
+        }
+        conn.sdo_write_typed(node_id, mapping, 0, &count)?;
+        conn.sdo_write_typed(node_id, communication, 1, &self.cob_id)
+    }
+}
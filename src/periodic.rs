@@ -0,0 +1,83 @@
+//! Background thread sending something every period, shared by the SYNC and TIME producers
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::{CanOpenError, Conn};
+
+#[derive(Debug)]
+pub(crate) struct PeriodicTask {
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<Result<(), CanOpenError>>>,
+}
+
+impl PeriodicTask {
+    /// Calls `tick` right away, then once every `period` until stopped or `tick` fails
+    pub(crate) fn start<F>(
+        name: &str,
+        conn: Conn,
+        period: Duration,
+        mut tick: F,
+    ) -> Result<Self, CanOpenError>
+    where
+        F: FnMut(&Conn) -> Result<(), CanOpenError> + Send + 'static,
+    {
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_running = running.clone();
+        let handle = std::thread::Builder::new()
+            .name(name.to_owned())
+            .spawn(move || {
+                // schedule against absolute deadlines, so the period doesn't drift by however long ticking takes
+                let mut deadline = Instant::now();
+                let result = loop {
+                    if !thread_running.load(Ordering::Relaxed) {
+                        break Ok(());
+                    }
+                    if let Err(e) = tick(&conn) {
+                        break Err(e);
+                    }
+                    deadline += period;
+                    let now = Instant::now();
+                    if deadline > now {
+                        std::thread::sleep(deadline - now);
+                    } else {
+                        // we fell behind by more than a period (eg. the thread wasn't scheduled),
+                        // skip the missed ticks rather than bursting
+                        deadline = now;
+                    }
+                };
+                thread_running.store(false, Ordering::Relaxed);
+                result
+            })
+            .map_err(CanOpenError::IOError)?;
+        Ok(Self {
+            running,
+            handle: Some(handle),
+        })
+    }
+
+    pub(crate) fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Returns the error that stopped the task early, if any
+    pub(crate) fn stop(&mut self) -> Result<(), CanOpenError> {
+        self.running.store(false, Ordering::Relaxed);
+        match self.handle.take() {
+            Some(handle) => handle.join().unwrap_or_else(|_| {
+                Err(CanOpenError::ConnectionError(
+                    "periodic task thread panicked".to_owned(),
+                ))
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for PeriodicTask {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
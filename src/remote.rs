@@ -0,0 +1,254 @@
+//! A node on the bus, so the node id doesn't have to be repeated everywhere
+//! ```no_run
+//! # use canopeners::{Conn, eds, remote::RemoteNode};
+//! let mut conn = Conn::new("vcan0").unwrap();
+//! let mut drive = RemoteNode::new(&mut conn, 5).with_description(eds::load("drive.eds").unwrap());
+//! drive.start().unwrap();
+//! drive.write_named("Controlword", &0x0Fu16).unwrap();
+//! let statusword: u16 = drive.read(0x6041, 0).unwrap();
+//! println!("{:?}", drive.identity().unwrap());
+//! ```
+//! The node borrows (`&mut Conn`) or owns (`Conn`) its connection. Heartbeats and EMCYs are
+//! tracked from what's fed to `process`, the way the consumers in `emcy` and `time` work.
+
+use std::borrow::BorrowMut;
+use std::time::Instant;
+
+use crate::lss::LssIdentity;
+use crate::named;
+use crate::od::{ObjectDictionary, ValueExpr, Variable};
+use crate::pdo::{PdoConfig, PdoDirection};
+use crate::value::{Entry, Readable, SdoValue, Writable};
+use crate::{CanOpenError, Conn, Emergency, GuardStatus, Message, Nmt, NmtFunction};
+
+fn no_description(node_id: u8) -> CanOpenError {
+    CanOpenError::NotFound(format!("node {node_id} has no description"))
+}
+
+#[derive(Debug)]
+pub struct RemoteNode<C: BorrowMut<Conn> = Conn> {
+    conn: C,
+    node_id: u8,
+    description: Option<ObjectDictionary>,
+    state: Option<GuardStatus>,
+    last_heartbeat: Option<Instant>,
+    last_emergency: Option<Emergency>,
+}
+
+impl<C: BorrowMut<Conn>> RemoteNode<C> {
+    pub fn new(conn: C, node_id: u8) -> Self {
+        Self {
+            conn,
+            node_id,
+            description: None,
+            state: None,
+            last_heartbeat: None,
+            last_emergency: None,
+        }
+    }
+
+    /// The node's EDS/DCF, for addressing objects by name
+    pub fn with_description(mut self, description: ObjectDictionary) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn node_id(&self) -> u8 {
+        self.node_id
+    }
+
+    pub fn description(&self) -> Option<&ObjectDictionary> {
+        self.description.as_ref()
+    }
+
+    pub fn conn(&mut self) -> &mut Conn {
+        self.conn.borrow_mut()
+    }
+
+    /// Gives the connection back
+    pub fn into_conn(self) -> C {
+        self.conn
+    }
+
+    pub fn sdo_read(&mut self, index: u16, sub_index: u8) -> Result<Box<[u8]>, CanOpenError> {
+        let node_id = self.node_id;
+        self.conn().sdo_read(node_id, index, sub_index)
+    }
+
+    pub fn sdo_write(
+        &mut self,
+        index: u16,
+        sub_index: u8,
+        data: &[u8],
+    ) -> Result<(), CanOpenError> {
+        let node_id = self.node_id;
+        self.conn().sdo_write(node_id, index, sub_index, data)
+    }
+
+    pub fn read<T: SdoValue>(&mut self, index: u16, sub_index: u8) -> Result<T, CanOpenError> {
+        let node_id = self.node_id;
+        self.conn().sdo_read_typed(node_id, index, sub_index)
+    }
+
+    pub fn write<T: SdoValue>(
+        &mut self,
+        index: u16,
+        sub_index: u8,
+        value: &T,
+    ) -> Result<(), CanOpenError> {
+        let node_id = self.node_id;
+        self.conn()
+            .sdo_write_typed(node_id, index, sub_index, value)
+    }
+
+    pub fn read_entry<T: SdoValue, A: Readable>(
+        &mut self,
+        entry: &Entry<T, A>,
+    ) -> Result<T, CanOpenError> {
+        let node_id = self.node_id;
+        entry.read(self.conn(), node_id)
+    }
+
+    pub fn write_entry<T: SdoValue, A: Writable>(
+        &mut self,
+        entry: &Entry<T, A>,
+        value: T,
+    ) -> Result<(), CanOpenError> {
+        let node_id = self.node_id;
+        entry.write(self.conn(), node_id, value)
+    }
+
+    /// The described variable called `name`, see `od::ObjectDictionary::find`
+    pub fn variable(&self, name: &str) -> Result<&Variable, CanOpenError> {
+        self.description
+            .as_ref()
+            .ok_or_else(|| no_description(self.node_id))?
+            .find(name)
+    }
+
+    /// Where `name` lives, if it's declared as `T`
+    fn typed_variable<T: SdoValue>(&self, name: &str) -> Result<(u16, u8), CanOpenError> {
+        let variable = self.variable(name)?;
+        if variable.data_type != T::DATA_TYPE {
+            return Err(CanOpenError::ParseError(format!(
+                "{name:?} is a {:?}, not a {:?}",
+                variable.data_type,
+                T::DATA_TYPE
+            )));
+        }
+        Ok((variable.index, variable.sub_index))
+    }
+
+    pub fn read_named<T: SdoValue>(&mut self, name: &str) -> Result<T, CanOpenError> {
+        let (index, sub_index) = self.typed_variable::<T>(name)?;
+        self.read(index, sub_index)
+    }
+
+    pub fn write_named<T: SdoValue>(&mut self, name: &str, value: &T) -> Result<(), CanOpenError> {
+        let (index, sub_index) = self.typed_variable::<T>(name)?;
+        self.write(index, sub_index, value)
+    }
+
+    /// See `named::read_by_name`
+    pub fn read_by_name(&mut self, name: &str) -> Result<ValueExpr, CanOpenError> {
+        let node_id = self.node_id;
+        let description = self
+            .description
+            .as_ref()
+            .ok_or_else(|| no_description(node_id))?;
+        named::read_by_name(self.conn.borrow_mut(), node_id, description, name)
+    }
+
+    /// See `named::write_by_name`
+    pub fn write_by_name(&mut self, name: &str, value: &str) -> Result<(), CanOpenError> {
+        let node_id = self.node_id;
+        let description = self
+            .description
+            .as_ref()
+            .ok_or_else(|| no_description(node_id))?;
+        named::write_by_name(self.conn.borrow_mut(), node_id, description, name, value)
+    }
+
+    fn nmt(&mut self, function: NmtFunction) -> Result<(), CanOpenError> {
+        let message = Message::Nmt(Nmt::new(function, self.node_id));
+        self.conn().send(&message)
+    }
+
+    pub fn start(&mut self) -> Result<(), CanOpenError> {
+        self.nmt(NmtFunction::StartRemoteNode)
+    }
+
+    pub fn stop(&mut self) -> Result<(), CanOpenError> {
+        self.nmt(NmtFunction::StopRemoteNode)
+    }
+
+    pub fn enter_pre_operational(&mut self) -> Result<(), CanOpenError> {
+        self.nmt(NmtFunction::EnterPreOperational)
+    }
+
+    pub fn reset(&mut self) -> Result<(), CanOpenError> {
+        self.nmt(NmtFunction::ResetNode)
+    }
+
+    pub fn reset_communication(&mut self) -> Result<(), CanOpenError> {
+        self.nmt(NmtFunction::ResetCommunication)
+    }
+
+    /// Keeps track of this node's heartbeats and EMCYs, everything else is ignored
+    pub fn process(&mut self, message: &Message) {
+        match message {
+            Message::Guard(guard) if guard.node_id() == self.node_id => {
+                self.state = Some(guard.status());
+                self.last_heartbeat = Some(Instant::now());
+            }
+            Message::Emergency(emergency) if emergency.node_id() == self.node_id => {
+                self.last_emergency = Some(emergency.clone());
+            }
+            _ => {}
+        }
+    }
+
+    /// NMT state from the latest heartbeat (or boot-up)
+    pub fn state(&self) -> Option<GuardStatus> {
+        self.state
+    }
+
+    /// When the latest heartbeat was processed
+    pub fn last_heartbeat(&self) -> Option<Instant> {
+        self.last_heartbeat
+    }
+
+    /// Latest EMCY, including error resets. See `emcy::EmergencyConsumer` for the active errors.
+    pub fn last_emergency(&self) -> Option<&Emergency> {
+        self.last_emergency.as_ref()
+    }
+
+    /// The identity object (0x1018)
+    pub fn identity(&mut self) -> Result<LssIdentity, CanOpenError> {
+        Ok(LssIdentity {
+            vendor_id: self.read(0x1018, 1)?,
+            product_code: self.read(0x1018, 2)?,
+            revision_number: self.read(0x1018, 3)?,
+            serial_number: self.read(0x1018, 4)?,
+        })
+    }
+
+    pub fn pdo_config(
+        &mut self,
+        direction: PdoDirection,
+        pdo: u16,
+    ) -> Result<PdoConfig, CanOpenError> {
+        let node_id = self.node_id;
+        PdoConfig::read(self.conn(), node_id, direction, pdo)
+    }
+
+    pub fn configure_pdo(
+        &mut self,
+        direction: PdoDirection,
+        pdo: u16,
+        config: &PdoConfig,
+    ) -> Result<(), CanOpenError> {
+        let node_id = self.node_id;
+        config.write(self.conn(), node_id, direction, pdo)
+    }
+}
@@ -0,0 +1,189 @@
+//! Finds out what's on the bus
+//! ```no_run
+//! # use canopeners::{Conn, scan::Scanner};
+//! # use std::time::Duration;
+//! let mut conn = Conn::new("vcan0").unwrap();
+//! let nodes = Scanner::new()
+//!     .with_timeout(Duration::from_millis(20))
+//!     .scan(&mut conn)
+//!     .unwrap();
+//! for node in nodes {
+//!     println!("{}: {:?} {:?}", node.node_id, node.device_name, node.lss_identity());
+//! }
+//! ```
+//! Every node id gets an SDO upload of the device type (0x1000). Nodes that answer are asked for
+//! their identity (0x1018) and names (0x1008, 0x1009, 0x100A). Heartbeats and boot-ups seen
+//! during the scan are collected too, so nodes without an SDO server still show up.
+
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use crate::lss::LssIdentity;
+use crate::value::SdoValue;
+use crate::{
+    CanOpenError, Conn, GuardStatus, Message, ReqRes, Sdo, SdoCmd, SdoCmdInitiatePayload,
+    SdoCmdInitiateUploadRx, SdoCmdInitiateUploadTx,
+};
+
+pub const DEFAULT_SCAN_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// One node found on the bus
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NodeInfo {
+    pub node_id: u8,
+    /// Whether the node answered the SDO upload of 0x1000, even if with an abort
+    pub sdo_responder: bool,
+    /// 0x1000
+    pub device_type: Option<u32>,
+    /// Latest state from a heartbeat or boot-up seen during the scan
+    pub state: Option<GuardStatus>,
+    /// A boot-up was seen during the scan
+    pub booted: bool,
+    /// 0x1018sub1 to 0x1018sub4
+    pub vendor_id: Option<u32>,
+    pub product_code: Option<u32>,
+    pub revision_number: Option<u32>,
+    pub serial_number: Option<u32>,
+    /// 0x1008
+    pub device_name: Option<String>,
+    /// 0x1009
+    pub hardware_version: Option<String>,
+    /// 0x100A
+    pub software_version: Option<String>,
+}
+
+impl NodeInfo {
+    fn new(node_id: u8) -> Self {
+        Self {
+            node_id,
+            ..Default::default()
+        }
+    }
+
+    /// The LSS address, if the whole identity object could be read
+    pub fn lss_identity(&self) -> Option<LssIdentity> {
+        Some(LssIdentity {
+            vendor_id: self.vendor_id?,
+            product_code: self.product_code?,
+            revision_number: self.revision_number?,
+            serial_number: self.serial_number?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Scanner {
+    timeout: Duration,
+    node_ids: RangeInclusive<u8>,
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Entries the node doesn't have (or sends with the wrong size) are None
+fn optional<T: SdoValue>(
+    conn: &mut Conn,
+    node_id: u8,
+    index: u16,
+    sub_index: u8,
+) -> Result<Option<T>, CanOpenError> {
+    match conn.sdo_read_typed(node_id, index, sub_index) {
+        Ok(value) => Ok(Some(value)),
+        Err(CanOpenError::SdoAbortTransfer { .. } | CanOpenError::ParseError(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+impl Scanner {
+    pub fn new() -> Self {
+        Self {
+            timeout: DEFAULT_SCAN_TIMEOUT,
+            node_ids: 1..=127,
+        }
+    }
+
+    /// How long to wait for each node to answer
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Node ids to probe, 1..=127 by default
+    pub fn with_node_ids(mut self, node_ids: RangeInclusive<u8>) -> Self {
+        self.node_ids = node_ids;
+        self
+    }
+
+    /// Probes every node id, then reads the identity of the nodes that answered.
+    /// Nodes are sorted by node id.
+    pub fn scan(&self, conn: &mut Conn) -> Result<Vec<NodeInfo>, CanOpenError> {
+        let mut nodes = BTreeMap::new();
+        for node_id in self.node_ids.clone() {
+            self.probe(conn, node_id, &mut nodes)?;
+        }
+        for node in nodes.values_mut().filter(|n| n.sdo_responder) {
+            let node_id = node.node_id;
+            node.vendor_id = optional(conn, node_id, 0x1018, 1)?;
+            node.product_code = optional(conn, node_id, 0x1018, 2)?;
+            node.revision_number = optional(conn, node_id, 0x1018, 3)?;
+            node.serial_number = optional(conn, node_id, 0x1018, 4)?;
+            node.device_name = optional(conn, node_id, 0x1008, 0)?;
+            node.hardware_version = optional(conn, node_id, 0x1009, 0)?;
+            node.software_version = optional(conn, node_id, 0x100A, 0)?;
+        }
+        Ok(nodes.into_values().collect())
+    }
+
+    /// Uploads 0x1000 from `node_id`, recording any heartbeats that arrive meanwhile
+    fn probe(
+        &self,
+        conn: &mut Conn,
+        node_id: u8,
+        nodes: &mut BTreeMap<u8, NodeInfo>,
+    ) -> Result<(), CanOpenError> {
+        conn.send(&Message::Sdo(Sdo {
+            node_id,
+            reqres: ReqRes::Req,
+            command: SdoCmd::InitiateUploadRx(SdoCmdInitiateUploadRx {
+                index: 0x1000,
+                sub_index: 0,
+            }),
+        }))?;
+        let response = conn.recv_matching(Instant::now() + self.timeout, |message| {
+            match message {
+                Message::Guard(guard) => {
+                    let node = nodes
+                        .entry(guard.node_id())
+                        .or_insert_with(|| NodeInfo::new(guard.node_id()));
+                    node.state = Some(guard.status());
+                    node.booted |= guard.status() == GuardStatus::Boot;
+                    None
+                }
+                Message::Sdo(sdo) if sdo.node_id == node_id && sdo.reqres == ReqRes::Res => {
+                    match sdo.command {
+                        // a segmented 0x1000 would be odd, the node still answered though
+                        SdoCmd::InitiateUploadTx(SdoCmdInitiateUploadTx {
+                            payload: SdoCmdInitiatePayload::Expedited(data),
+                            ..
+                        }) => Some(u32::decode(&data).ok()),
+                        SdoCmd::InitiateUploadTx(_) | SdoCmd::AbortTransfer(_) => Some(None),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        })?;
+        if let Some(device_type) = response {
+            let node = nodes
+                .entry(node_id)
+                .or_insert_with(|| NodeInfo::new(node_id));
+            node.sdo_responder = true;
+            node.device_type = device_type;
+        }
+        Ok(())
+    }
+}
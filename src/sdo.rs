@@ -0,0 +1,277 @@
+//! SDO channels on other COB-IDs than the default 0x600/0x580 + node id, and streaming transfers
+//! ```no_run
+//! # use canopeners::{Conn, sdo::SdoChannel};
+//! let mut conn = Conn::new("vcan0").unwrap();
+//! // the gateway's second server SDO (0x1201), forwarding to the device behind it
+//! let channel = SdoChannel::read_server(&mut conn, 5, 1).unwrap().with_node_id(0x45);
+//! conn.set_sdo_channel(channel).unwrap();
+//! let device_type = conn.sdo_read(0x45, 0x1000, 0).unwrap();
+//! ```
+//! Channels mirror the SDO server (0x1200-0x127F) and client (0x1280-0x12FF) parameter records.
+//! Once registered on a `Conn`, SDO messages to or from the channel's node id go over its COB-IDs,
+//! for clients (`sdo_read`, `sdo_write`) as well as servers (`recv`, `send`).
+//!
+//! Large objects (firmware images, logs) can be streamed with `Conn::sdo_write_from` and
+//! `Conn::sdo_read_into`, which report an `SdoProgress` after every segment:
+//! ```no_run
+//! # use canopeners::Conn;
+//! # use std::ops::ControlFlow;
+//! let mut conn = Conn::new("vcan0").unwrap();
+//! let image = std::fs::File::open("firmware.bin").unwrap();
+//! let len = image.metadata().unwrap().len() as u32;
+//! conn.sdo_write_from(5, 0x1F50, 1, image, len, |progress| {
+//!     println!("{}/{:?}", progress.done, progress.total);
+//!     ControlFlow::Continue(()) // Break(()) aborts the transfer
+//! })
+//! .unwrap();
+//! ```
+
+use std::time::Duration;
+
+use crate::enums::AbortCode;
+use crate::{CanOpenError, Conn};
+
+/// COB-ID bit 31: the channel doesn't exist or isn't in use
+pub const COB_ID_INVALID: u32 = 1 << 31;
+/// COB-ID bit 30: the channel was assigned dynamically (by an SDO manager)
+pub const COB_ID_DYNAMIC: u32 = 1 << 30;
+/// COB-ID bit 29: 29bit CAN id, which we don't support
+pub const COB_ID_EXTENDED: u32 = 1 << 29;
+
+const SERVER_PARAMETER: u16 = 0x1200;
+const CLIENT_PARAMETER: u16 = 0x1280;
+
+/// One SDO client/server parameter record
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SdoChannel {
+    /// COB-ID client -> server (sub1), requests
+    pub cob_id_client_to_server: u32,
+    /// COB-ID server -> client (sub2), responses
+    pub cob_id_server_to_client: u32,
+    /// The server's node id (sub3 of client parameters).
+    /// `Conn` addresses the channel by it, it doesn't need to be the node id of a real node.
+    pub node_id: u8,
+}
+
+impl SdoChannel {
+    pub fn new(node_id: u8, request_can_id: u16, response_can_id: u16) -> Self {
+        Self {
+            cob_id_client_to_server: request_can_id as u32,
+            cob_id_server_to_client: response_can_id as u32,
+            node_id,
+        }
+    }
+
+    /// 0x600 + node id for requests, 0x580 + node id for responses
+    pub fn default_for(node_id: u8) -> Self {
+        Self::new(node_id, 0x600 + node_id as u16, 0x580 + node_id as u16)
+    }
+
+    pub fn with_node_id(mut self, node_id: u8) -> Self {
+        self.node_id = node_id;
+        self
+    }
+
+    /// Marks both COB-IDs as dynamically assigned
+    pub fn with_dynamic(mut self) -> Self {
+        self.cob_id_client_to_server |= COB_ID_DYNAMIC;
+        self.cob_id_server_to_client |= COB_ID_DYNAMIC;
+        self
+    }
+
+    /// Both directions are in use
+    pub fn is_valid(&self) -> bool {
+        (self.cob_id_client_to_server | self.cob_id_server_to_client) & COB_ID_INVALID == 0
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        (self.cob_id_client_to_server | self.cob_id_server_to_client) & COB_ID_DYNAMIC != 0
+    }
+
+    fn can_id(cob_id: u32) -> Result<u16, CanOpenError> {
+        if cob_id & COB_ID_EXTENDED != 0 {
+            return Err(CanOpenError::CanVersion(format!(
+                "SDO COB-ID {cob_id:#x} selects an extended (29bit) id"
+            )));
+        }
+        Ok((cob_id & 0x7FF) as u16)
+    }
+
+    pub fn request_can_id(&self) -> Result<u16, CanOpenError> {
+        Self::can_id(self.cob_id_client_to_server)
+    }
+
+    pub fn response_can_id(&self) -> Result<u16, CanOpenError> {
+        Self::can_id(self.cob_id_server_to_client)
+    }
+
+    /// SDO server parameter `channel` (0x1200 + channel) of `node_id`.
+    /// The channel's node id is `node_id`, the server itself.
+    pub fn read_server(conn: &mut Conn, node_id: u8, channel: u8) -> Result<Self, CanOpenError> {
+        let index = parameter_index(SERVER_PARAMETER, channel)?;
+        Ok(Self {
+            cob_id_client_to_server: conn.sdo_read_typed(node_id, index, 1)?,
+            cob_id_server_to_client: conn.sdo_read_typed(node_id, index, 2)?,
+            node_id,
+        })
+    }
+
+    /// SDO client parameter `channel` (0x1280 + channel) of `node_id`
+    pub fn read_client(conn: &mut Conn, node_id: u8, channel: u8) -> Result<Self, CanOpenError> {
+        let index = parameter_index(CLIENT_PARAMETER, channel)?;
+        Ok(Self {
+            cob_id_client_to_server: conn.sdo_read_typed(node_id, index, 1)?,
+            cob_id_server_to_client: conn.sdo_read_typed(node_id, index, 2)?,
+            node_id: conn.sdo_read_typed(node_id, index, 3)?,
+        })
+    }
+
+    /// Writes the COB-IDs to SDO server parameter `channel` of `node_id`.
+    /// 0x1200 is fixed, so `channel` has to be 1 or more.
+    pub fn configure_server(
+        &self,
+        conn: &mut Conn,
+        node_id: u8,
+        channel: u8,
+    ) -> Result<(), CanOpenError> {
+        if channel == 0 {
+            return Err(CanOpenError::BadMessage(
+                "the default SDO server channel (0x1200) can't be configured".to_owned(),
+            ));
+        }
+        self.write_cob_ids(conn, node_id, parameter_index(SERVER_PARAMETER, channel)?)
+    }
+
+    /// Writes the channel to SDO client parameter `channel` of `node_id`
+    pub fn configure_client(
+        &self,
+        conn: &mut Conn,
+        node_id: u8,
+        channel: u8,
+    ) -> Result<(), CanOpenError> {
+        let index = parameter_index(CLIENT_PARAMETER, channel)?;
+        self.write_cob_ids(conn, node_id, index)?;
+        conn.sdo_write_typed(node_id, index, 3, &self.node_id)
+    }
+
+    /// COB-IDs may only change while the channel is invalid, so it's invalidated first
+    fn write_cob_ids(&self, conn: &mut Conn, node_id: u8, index: u16) -> Result<(), CanOpenError> {
+        conn.sdo_write_typed(
+            node_id,
+            index,
+            1,
+            &(self.cob_id_client_to_server | COB_ID_INVALID),
+        )?;
+        conn.sdo_write_typed(node_id, index, 2, &self.cob_id_server_to_client)?;
+        conn.sdo_write_typed(node_id, index, 1, &self.cob_id_client_to_server)
+    }
+}
+
+fn parameter_index(base: u16, channel: u8) -> Result<u16, CanOpenError> {
+    if channel > 0x7F {
+        return Err(CanOpenError::OverflowError(format!(
+            "SDO channel {channel} is over 127"
+        )));
+    }
+    Ok(base + channel as u16)
+}
+
+/// How far a streaming transfer (`Conn::sdo_write_from`, `Conn::sdo_read_into`) got
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SdoProgress {
+    /// Bytes transferred so far
+    pub done: u64,
+    /// None for uploads of unannounced size
+    pub total: Option<u64>,
+}
+
+/// When `Conn::sdo_read` and `Conn::sdo_write` try a failed transfer again, see `Conn::set_sdo_retry_policy`.
+/// Retries start over from the initiate phase, after waiting for the backoff
+/// (there's no block transfer yet, so that's expedited and segmented transfers).
+/// Every attempt is logged through the `log` crate. The default is a single attempt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+    retry_on_timeout: bool,
+    retry_on_io_error: bool,
+    abort_codes: Vec<AbortCode>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl RetryPolicy {
+    /// Up to `max_attempts` attempts in total. Timeouts and aborts that hint at a garbled bus
+    /// (toggle bit, SDO timeout, block size, sequence number, CRC) are retried,
+    /// backing off from 10ms, doubling up to 1s.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+            retry_on_timeout: true,
+            retry_on_io_error: false,
+            abort_codes: vec![
+                AbortCode::ToggleBitNotAlternated,
+                AbortCode::SdoProtocolTimedOut,
+                AbortCode::InvalidBlockSize,
+                AbortCode::InvalidSequenceNumber,
+                AbortCode::CrcError,
+            ],
+        }
+    }
+
+    /// Wait before the first retry, doubled for every retry after it up to `max`
+    pub fn with_backoff(mut self, first: Duration, max: Duration) -> Self {
+        self.backoff = first;
+        self.max_backoff = max;
+        self
+    }
+
+    pub fn with_retry_on_timeout(mut self, retry: bool) -> Self {
+        self.retry_on_timeout = retry;
+        self
+    }
+
+    /// eg. the socket's send buffer running full
+    pub fn with_retry_on_io_error(mut self, retry: bool) -> Self {
+        self.retry_on_io_error = retry;
+        self
+    }
+
+    /// Replaces the abort codes (from either side) that are retried
+    pub fn with_abort_codes(mut self, abort_codes: impl IntoIterator<Item = AbortCode>) -> Self {
+        self.abort_codes = abort_codes.into_iter().collect();
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub fn is_retryable(&self, error: &CanOpenError) -> bool {
+        match error {
+            CanOpenError::Timeout(_) => self.retry_on_timeout,
+            CanOpenError::IOError(_) => self.retry_on_io_error,
+            CanOpenError::SdoAbortTransfer { abort_code, .. } => {
+                self.abort_codes.contains(abort_code)
+            }
+            _ => false,
+        }
+    }
+
+    /// Wait after failed attempt number `attempt` (from 1)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |b| b.min(self.max_backoff))
+    }
+}
@@ -0,0 +1,268 @@
+//! SYNC producer and consumer
+//! The SYNC producer broadcasts a SYNC message every communication cycle period (0x1006).
+//! Synchronous PDOs are sent/applied relative to these, so the period should be as steady as possible.
+//! The producer runs on its own thread and owns its `Conn`:
+//! ```no_run
+//! # use canopeners::{Conn, sync::{SyncConfig, SyncProducer}};
+//! let conn = Conn::new("vcan0").unwrap();
+//! // SYNC every 10ms, counter wrapping after 16
+//! let producer = SyncProducer::start(conn, SyncConfig::new(0x80, 10_000, 16)).unwrap();
+//! // ...
+//! producer.stop().unwrap();
+//! ```
+//!
+//! On the consuming side, `SyncCycleConsumer` groups the PDOs received between two SYNCs
+//! into a `SyncCycle`, which is convenient for processing the bus cycle by cycle:
+//! ```no_run
+//! # use canopeners::{Conn, sync::SyncCycleConsumer};
+//! let conn = Conn::new("vcan0").unwrap();
+//! // synchronous window length (0x1007) of 5ms, expecting TPDO1 of nodes 1 and 2
+//! let mut cycles = SyncCycleConsumer::new()
+//!     .with_window_length_us(5_000)
+//!     .with_expected_pdos([0x181, 0x182]);
+//! loop {
+//!     let cycle = cycles.next_cycle(&conn).unwrap();
+//!     if !cycle.missing.is_empty() {
+//!         println!("cycle {:?} is missing {:x?}", cycle.counter, cycle.missing);
+//!     }
+//! }
+//! ```
+
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+use crate::periodic::PeriodicTask;
+use crate::{CanOpenError, Conn, Message, Pdo, Sync};
+
+/// SYNC related entries of the producer's object dictionary
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncConfig {
+    /// COB-ID SYNC message (0x1005)
+    /// bits 0..=10 are the CAN id, bit 29 would select an extended (29bit) id, which we don't support.
+    /// Bit 30 ("this node generates SYNC") is ignored, starting a producer implies it.
+    pub cob_id: u32,
+    /// Communication cycle period (0x1006) in microseconds
+    pub cycle_period_us: u32,
+    /// Synchronous counter overflow value (0x1019)
+    /// 0 means SYNCs carry no counter, 2..=240 means the counter wraps after reaching this value
+    pub counter_overflow: u8,
+}
+
+impl SyncConfig {
+    pub fn new(cob_id: u32, cycle_period_us: u32, counter_overflow: u8) -> Self {
+        Self {
+            cob_id,
+            cycle_period_us,
+            counter_overflow,
+        }
+    }
+
+    pub fn cycle_period(&self) -> Duration {
+        Duration::from_micros(self.cycle_period_us as u64)
+    }
+
+    fn can_id(&self) -> Result<u16, CanOpenError> {
+        if self.cob_id & (1 << 29) != 0 {
+            return Err(CanOpenError::CanVersion(
+                "SYNC COB-ID selects an extended (29bit) id".to_owned(),
+            ));
+        }
+        Ok((self.cob_id & 0x7FF) as u16)
+    }
+
+    fn validate(&self) -> Result<(), CanOpenError> {
+        self.can_id()?;
+        if self.cycle_period_us == 0 {
+            return Err(CanOpenError::BadMessage(
+                "communication cycle period of 0 disables SYNC".to_owned(),
+            ));
+        }
+        if self.counter_overflow == 1 || self.counter_overflow > 240 {
+            return Err(CanOpenError::BadMessage(format!(
+                "synchronous counter overflow value must be 0 or 2..=240, got {}",
+                self.counter_overflow
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for SyncConfig {
+    /// Default COB-ID, no counter, 1ms period
+    fn default() -> Self {
+        Self::new(0x80, 1000, 0)
+    }
+}
+
+/// Counts from 1 up to the overflow value, then starts over at 1
+#[derive(Clone, Debug)]
+struct SyncCounter {
+    overflow: u8,
+    next: u8,
+}
+
+impl SyncCounter {
+    fn new(overflow: u8) -> Self {
+        Self { overflow, next: 1 }
+    }
+
+    fn advance(&mut self) -> Option<u8> {
+        if self.overflow == 0 {
+            return None;
+        }
+        let counter = self.next;
+        self.next = if counter >= self.overflow {
+            1
+        } else {
+            counter + 1
+        };
+        Some(counter)
+    }
+}
+
+/// Sends SYNC messages on a background thread until stopped or dropped
+#[derive(Debug)]
+pub struct SyncProducer {
+    task: PeriodicTask,
+}
+
+impl SyncProducer {
+    pub fn start(conn: Conn, config: SyncConfig) -> Result<Self, CanOpenError> {
+        config.validate()?;
+        let cob_id = config.can_id()?;
+        let mut counter = SyncCounter::new(config.counter_overflow);
+        let task = PeriodicTask::start(
+            "canopeners-sync",
+            conn,
+            config.cycle_period(),
+            move |conn| conn.send(&Message::Sync(Sync::with_cob_id(cob_id, counter.advance()))),
+        )?;
+        Ok(Self { task })
+    }
+
+    /// False once the producer was stopped or sending failed
+    pub fn is_running(&self) -> bool {
+        self.task.is_running()
+    }
+
+    /// Stops the producer, returns the error that stopped it early, if any
+    pub fn stop(mut self) -> Result<(), CanOpenError> {
+        self.task.stop()
+    }
+}
+
+/// A PDO received during a `SyncCycle`
+#[derive(Clone, Debug)]
+pub struct CyclePdo {
+    pub pdo: Pdo,
+    /// Time since the SYNC that opened the cycle
+    pub offset: Duration,
+    /// False if the PDO arrived after the synchronous window closed
+    pub in_window: bool,
+}
+
+/// Everything received between two SYNCs
+#[derive(Clone, Debug)]
+pub struct SyncCycle {
+    /// Counter of the SYNC that opened the cycle, if SYNCs carry one
+    pub counter: Option<u8>,
+    /// When the SYNC that opened the cycle arrived
+    pub timestamp: Instant,
+    /// PDOs in order of arrival
+    pub pdos: Vec<CyclePdo>,
+    /// COB-IDs of expected PDOs that were not received this cycle
+    pub missing: Vec<u16>,
+}
+
+impl SyncCycle {
+    /// PDOs that arrived after the synchronous window closed
+    pub fn late_pdos(&self) -> impl Iterator<Item = &CyclePdo> {
+        self.pdos.iter().filter(|p| !p.in_window)
+    }
+
+    /// Last PDO received with `cob_id` this cycle
+    pub fn pdo(&self, cob_id: u16) -> Option<&Pdo> {
+        self.pdos
+            .iter()
+            .rev()
+            .map(|p| &p.pdo)
+            .find(|p| p.cob_id() == cob_id)
+    }
+}
+
+/// Groups received PDOs into `SyncCycle`s
+#[derive(Clone, Debug, Default)]
+pub struct SyncCycleConsumer {
+    window: Option<Duration>,
+    expected: BTreeSet<u16>,
+    current: Option<SyncCycle>,
+}
+
+impl SyncCycleConsumer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Synchronous window length (0x1007) in microseconds, 0 disables the window
+    pub fn with_window_length_us(mut self, window_length_us: u32) -> Self {
+        self.window = match window_length_us {
+            0 => None,
+            us => Some(Duration::from_micros(us as u64)),
+        };
+        self
+    }
+
+    /// COB-IDs of PDOs that should arrive every cycle, see `SyncCycle::missing`
+    pub fn with_expected_pdos(mut self, cob_ids: impl IntoIterator<Item = u16>) -> Self {
+        self.expected.extend(cob_ids);
+        self
+    }
+
+    /// Handle one message received at `timestamp`.
+    /// Returns the previous cycle once the SYNC opening the next one arrives.
+    /// PDOs received before the first SYNC are dropped.
+    pub fn process(&mut self, message: &Message, timestamp: Instant) -> Option<SyncCycle> {
+        match message {
+            Message::Sync(sync) => {
+                let next = SyncCycle {
+                    counter: sync.counter,
+                    timestamp,
+                    pdos: Vec::new(),
+                    missing: Vec::new(),
+                };
+                self.current
+                    .replace(next)
+                    .map(|cycle| self.close_cycle(cycle))
+            }
+            Message::Pdo(pdo) => {
+                if let Some(cycle) = &mut self.current {
+                    let offset = timestamp.saturating_duration_since(cycle.timestamp);
+                    let in_window = self.window.is_none_or(|w| offset <= w);
+                    cycle.pdos.push(CyclePdo {
+                        pdo: pdo.clone(),
+                        offset,
+                        in_window,
+                    });
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn close_cycle(&self, mut cycle: SyncCycle) -> SyncCycle {
+        let received: BTreeSet<u16> = cycle.pdos.iter().map(|p| p.pdo.cob_id()).collect();
+        cycle.missing = self.expected.difference(&received).copied().collect();
+        cycle
+    }
+
+    /// Receive from `conn` until a full cycle was seen
+    pub fn next_cycle(&mut self, conn: &Conn) -> Result<SyncCycle, CanOpenError> {
+        loop {
+            let message = conn.recv()?;
+            if let Some(cycle) = self.process(&message, Instant::now()) {
+                return Ok(cycle);
+            }
+        }
+    }
+}
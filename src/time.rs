@@ -0,0 +1,97 @@
+//! TIME producer and consumer
+//! The TIME producer broadcasts the wall clock, so nodes without an RTC can keep track of the date.
+//! ```no_run
+//! # use canopeners::{Conn, time::TimeProducer};
+//! # use std::time::Duration;
+//! let conn = Conn::new("vcan0").unwrap();
+//! let producer = TimeProducer::start(conn, Duration::from_secs(1)).unwrap();
+//! ```
+//! `TimeConsumer` tracks how far the bus time is from the local clock.
+
+use std::time::{Duration, SystemTime};
+
+use crate::periodic::PeriodicTask;
+use crate::{CanOpenError, Conn, Message, TimeOfDay};
+
+/// Broadcasts the system time on a background thread until stopped or dropped
+#[derive(Debug)]
+pub struct TimeProducer {
+    task: PeriodicTask,
+}
+
+impl TimeProducer {
+    pub fn start(conn: Conn, period: Duration) -> Result<Self, CanOpenError> {
+        let task = PeriodicTask::start("canopeners-time", conn, period, |conn| {
+            let now = TimeOfDay::try_from(SystemTime::now())?;
+            conn.send(&Message::Time(now))
+        })?;
+        Ok(Self { task })
+    }
+
+    /// False once the producer was stopped or sending failed
+    pub fn is_running(&self) -> bool {
+        self.task.is_running()
+    }
+
+    /// Stops the producer, returns the error that stopped it early, if any
+    pub fn stop(mut self) -> Result<(), CanOpenError> {
+        self.task.stop()
+    }
+}
+
+/// Latest difference between the bus time and the local clock
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeOffset {
+    /// Bus time minus local time, in milliseconds. Positive if the bus is ahead.
+    pub offset_ms: i64,
+    /// Local time when the TIME message was received
+    pub received_at: SystemTime,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TimeConsumer {
+    latest: Option<TimeOffset>,
+}
+
+impl TimeConsumer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handle any incoming message, non-TIME messages are ignored
+    pub fn process(&mut self, message: &Message) {
+        if let Message::Time(time) = message {
+            self.process_time(*time, SystemTime::now());
+        }
+    }
+
+    /// Handle a TIME message received at local time `received_at`
+    pub fn process_time(&mut self, time: TimeOfDay, received_at: SystemTime) {
+        let bus_time = SystemTime::from(time);
+        let offset_ms = match bus_time.duration_since(received_at) {
+            Ok(ahead) => ahead.as_millis() as i64,
+            Err(behind) => -(behind.duration().as_millis() as i64),
+        };
+        self.latest = Some(TimeOffset {
+            offset_ms,
+            received_at,
+        });
+    }
+
+    /// None until the first TIME message arrives
+    pub fn offset(&self) -> Option<TimeOffset> {
+        self.latest
+    }
+
+    /// Current bus time, estimated from the local clock and the latest offset
+    pub fn bus_time(&self) -> Option<SystemTime> {
+        self.latest.map(|o| {
+            let now = SystemTime::now();
+            if o.offset_ms >= 0 {
+                now + Duration::from_millis(o.offset_ms as u64)
+            } else {
+                now - Duration::from_millis(o.offset_ms.unsigned_abs())
+            }
+        })
+    }
+}
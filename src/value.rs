@@ -0,0 +1,398 @@
+//! Typed object dictionary entries
+//! `Entry<T, A>` knows where a value lives, its Rust type `T` and its access type `A`,
+//! so reading a write only entry (or writing a u16 into a u8) doesn't compile:
+//! ```no_run
+//! # use canopeners::{Conn, value::{Entry, ReadWrite}};
+//! const CONTROLWORD: Entry<u16, ReadWrite> = Entry::new(0x6040, 0);
+//! let mut conn = Conn::new("vcan0").unwrap();
+//! CONTROLWORD.write(&mut conn, 5, 0x0F).unwrap();
+//! let controlword: u16 = CONTROLWORD.read(&mut conn, 5).unwrap();
+//! ```
+//! Every CiA 301 basic data type has a `SdoValue` type, see `SdoValue::DATA_TYPE`.
+//! Plain `Conn::sdo_read_typed`/`Conn::sdo_write_typed` work without an `Entry`.
+//! `codegen` generates these from an EDS.
+
+use std::marker::PhantomData;
+
+use binrw::{BinRead, BinWrite};
+
+use crate::enums::DataType;
+use crate::{CanOpenError, Conn, TimeOfDay};
+
+/// Rust types that map to a CANopen data type, encoded like SDO transfers them
+pub trait SdoValue: Sized {
+    const DATA_TYPE: DataType;
+
+    fn encode(&self) -> Vec<u8>;
+    fn decode(data: &[u8]) -> Result<Self, CanOpenError>;
+}
+
+fn check_length(data: &[u8], expected: usize) -> Result<(), CanOpenError> {
+    if data.len() != expected {
+        return Err(CanOpenError::ParseError(format!(
+            "expected {expected} bytes, got {}",
+            data.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Decodes what was read from `index`/`sub_index`, which has to be the size of `T`
+pub(crate) fn decode_read<T: SdoValue>(
+    index: u16,
+    sub_index: u8,
+    data: &[u8],
+) -> Result<T, CanOpenError> {
+    if let Some(size) = T::DATA_TYPE.size() {
+        if data.len() != size {
+            return Err(CanOpenError::ParseError(format!(
+                "{index:#06x}sub{sub_index}: {:?} needs {size} bytes, got {}",
+                T::DATA_TYPE,
+                data.len()
+            )));
+        }
+    }
+    T::decode(data)
+}
+
+macro_rules! impl_sdo_value_le {
+    ($($t:ty => $data_type:ident),*) => {
+        $(
+            impl SdoValue for $t {
+                const DATA_TYPE: DataType = DataType::$data_type;
+
+                fn encode(&self) -> Vec<u8> {
+                    self.to_le_bytes().to_vec()
+                }
+
+                fn decode(data: &[u8]) -> Result<Self, CanOpenError> {
+                    check_length(data, std::mem::size_of::<$t>())?;
+                    Ok(<$t>::from_le_bytes(data.try_into().unwrap()))
+                }
+            }
+        )*
+    };
+}
+
+impl_sdo_value_le!(
+    i8 => Integer8,
+    i16 => Integer16,
+    i32 => Integer32,
+    i64 => Integer64,
+    u8 => Unsigned8,
+    u16 => Unsigned16,
+    u32 => Unsigned32,
+    u64 => Unsigned64,
+    f32 => Real32,
+    f64 => Real64
+);
+
+/// Integers without a native Rust type, stored in the next bigger one.
+/// `new` fails if the value doesn't fit, decoding sign extends.
+macro_rules! odd_sized_integer {
+    ($($(#[$doc:meta])* $name:ident($inner:ty, $bytes:expr) => $data_type:ident),*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name($inner);
+
+            impl $name {
+                const UNUSED_BITS: u32 = <$inner>::BITS - $bytes * 8;
+
+                pub fn new(value: $inner) -> Result<Self, CanOpenError> {
+                    if (value << Self::UNUSED_BITS) >> Self::UNUSED_BITS != value {
+                        return Err(CanOpenError::OverflowError(format!(
+                            "{value} doesn't fit in {}",
+                            stringify!($name)
+                        )));
+                    }
+                    Ok(Self(value))
+                }
+
+                pub fn get(self) -> $inner {
+                    self.0
+                }
+            }
+
+            impl TryFrom<$inner> for $name {
+                type Error = CanOpenError;
+
+                fn try_from(value: $inner) -> Result<Self, Self::Error> {
+                    Self::new(value)
+                }
+            }
+
+            impl From<$name> for $inner {
+                fn from(value: $name) -> Self {
+                    value.0
+                }
+            }
+
+            impl SdoValue for $name {
+                const DATA_TYPE: DataType = DataType::$data_type;
+
+                fn encode(&self) -> Vec<u8> {
+                    self.0.to_le_bytes()[..$bytes].to_vec()
+                }
+
+                fn decode(data: &[u8]) -> Result<Self, CanOpenError> {
+                    check_length(data, $bytes)?;
+                    let mut buffer = [0; std::mem::size_of::<$inner>()];
+                    buffer[..$bytes].copy_from_slice(data);
+                    let value = <$inner>::from_le_bytes(buffer);
+                    Ok(Self((value << Self::UNUSED_BITS) >> Self::UNUSED_BITS))
+                }
+            }
+        )*
+    };
+}
+
+odd_sized_integer!(
+    /// INTEGER24
+    I24(i32, 3) => Integer24,
+    /// INTEGER40
+    I40(i64, 5) => Integer40,
+    /// INTEGER48
+    I48(i64, 6) => Integer48,
+    /// INTEGER56
+    I56(i64, 7) => Integer56,
+    /// UNSIGNED24
+    U24(u32, 3) => Unsigned24,
+    /// UNSIGNED40
+    U40(u64, 5) => Unsigned40,
+    /// UNSIGNED48
+    U48(u64, 6) => Unsigned48,
+    /// UNSIGNED56
+    U56(u64, 7) => Unsigned56
+);
+
+/// BOOLEAN
+impl SdoValue for bool {
+    const DATA_TYPE: DataType = DataType::Boolean;
+
+    fn encode(&self) -> Vec<u8> {
+        vec![*self as u8]
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, CanOpenError> {
+        check_length(data, 1)?;
+        Ok(data[0] != 0)
+    }
+}
+
+/// VISIBLE_STRING
+impl SdoValue for String {
+    const DATA_TYPE: DataType = DataType::VisibleString;
+
+    fn encode(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, CanOpenError> {
+        // devices commonly pad strings with NULs
+        Ok(String::from_utf8_lossy(data)
+            .trim_end_matches('\0')
+            .to_owned())
+    }
+}
+
+/// OCTET_STRING
+impl SdoValue for Vec<u8> {
+    const DATA_TYPE: DataType = DataType::OctetString;
+
+    fn encode(&self) -> Vec<u8> {
+        self.clone()
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, CanOpenError> {
+        Ok(data.to_vec())
+    }
+}
+
+/// UNICODE_STRING, UTF-16 little endian on the wire
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UnicodeString(pub String);
+
+impl SdoValue for UnicodeString {
+    const DATA_TYPE: DataType = DataType::UnicodeString;
+
+    fn encode(&self) -> Vec<u8> {
+        self.0.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, CanOpenError> {
+        if !data.len().is_multiple_of(2) {
+            return Err(CanOpenError::ParseError(format!(
+                "UNICODE_STRING needs an even number of bytes, got {}",
+                data.len()
+            )));
+        }
+        let units: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        Ok(Self(
+            String::from_utf16_lossy(&units)
+                .trim_end_matches('\0')
+                .to_owned(),
+        ))
+    }
+}
+
+/// DOMAIN, arbitrary data like firmware images or logs
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Domain(pub Vec<u8>);
+
+impl SdoValue for Domain {
+    const DATA_TYPE: DataType = DataType::Domain;
+
+    fn encode(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, CanOpenError> {
+        Ok(Self(data.to_vec()))
+    }
+}
+
+/// TIME_OF_DAY
+impl SdoValue for TimeOfDay {
+    const DATA_TYPE: DataType = DataType::TimeOfDay;
+
+    fn encode(&self) -> Vec<u8> {
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        self.write(&mut cursor)
+            .expect("writing to a Vec can't fail");
+        cursor.into_inner()
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, CanOpenError> {
+        check_length(data, 6)?;
+        TimeOfDay::read(&mut std::io::Cursor::new(data))
+            .map_err(|e| CanOpenError::ParseError(format!("binrw err: {e}")))
+    }
+}
+
+/// TIME_DIFFERENCE, laid out like TIME_OF_DAY.
+/// Convert from/to `std::time::Duration` with `TryFrom`/`From`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TimeDifference {
+    /// Below 24h, only 28 bits go on the wire
+    pub ms: u32,
+    pub days: u16,
+}
+
+impl TimeDifference {
+    const MS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+    pub fn new(ms: u32, days: u16) -> Self {
+        Self { ms, days }
+    }
+}
+
+impl From<TimeDifference> for std::time::Duration {
+    fn from(t: TimeDifference) -> Self {
+        std::time::Duration::from_millis(t.days as u64 * TimeDifference::MS_PER_DAY + t.ms as u64)
+    }
+}
+
+impl TryFrom<std::time::Duration> for TimeDifference {
+    type Error = CanOpenError;
+
+    /// Fails for durations of 65536 days or more
+    fn try_from(duration: std::time::Duration) -> Result<Self, Self::Error> {
+        let ms = duration.as_millis();
+        let days = u16::try_from(ms / Self::MS_PER_DAY as u128)
+            .map_err(|e| CanOpenError::OverflowError(e.to_string()))?;
+        Ok(Self::new((ms % Self::MS_PER_DAY as u128) as u32, days))
+    }
+}
+
+impl SdoValue for TimeDifference {
+    const DATA_TYPE: DataType = DataType::TimeDifference;
+
+    fn encode(&self) -> Vec<u8> {
+        TimeOfDay::new(self.ms, self.days).encode()
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, CanOpenError> {
+        let t = TimeOfDay::decode(data)?;
+        Ok(Self::new(t.ms_since_midnight, t.days_since_1984))
+    }
+}
+
+/// Access type markers, see `Entry`
+pub trait Access {}
+pub trait Readable: Access {}
+pub trait Writable: Access {}
+
+/// ro
+pub struct ReadOnly;
+/// wo
+pub struct WriteOnly;
+/// rw, rwr and rww
+pub struct ReadWrite;
+/// const
+pub struct Const;
+
+impl Access for ReadOnly {}
+impl Access for WriteOnly {}
+impl Access for ReadWrite {}
+impl Access for Const {}
+impl Readable for ReadOnly {}
+impl Readable for ReadWrite {}
+impl Readable for Const {}
+impl Writable for WriteOnly {}
+impl Writable for ReadWrite {}
+
+/// Index and sub-index of a value of type `T`, with access type `A`
+pub struct Entry<T, A> {
+    index: u16,
+    sub_index: u8,
+    _marker: PhantomData<fn() -> (T, A)>,
+}
+
+// derives would require T and A to be Clone/Copy/Debug too
+impl<T, A> Clone for Entry<T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, A> Copy for Entry<T, A> {}
+
+impl<T, A> std::fmt::Debug for Entry<T, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Entry({:#06x}sub{})", self.index, self.sub_index)
+    }
+}
+
+impl<T, A> Entry<T, A> {
+    pub const fn new(index: u16, sub_index: u8) -> Self {
+        Self {
+            index,
+            sub_index,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn index(&self) -> u16 {
+        self.index
+    }
+
+    pub fn sub_index(&self) -> u8 {
+        self.sub_index
+    }
+}
+
+impl<T: SdoValue, A: Readable> Entry<T, A> {
+    pub fn read(&self, conn: &mut Conn, node_id: u8) -> Result<T, CanOpenError> {
+        conn.sdo_read_typed(node_id, self.index, self.sub_index)
+    }
+}
+
+impl<T: SdoValue, A: Writable> Entry<T, A> {
+    pub fn write(&self, conn: &mut Conn, node_id: u8, value: T) -> Result<(), CanOpenError> {
+        conn.sdo_write_typed(node_id, self.index, self.sub_index, &value)
+    }
+}
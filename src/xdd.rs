@@ -0,0 +1,411 @@
+//! XDD/XDC (CiA 311 XML device description) parser
+//! Produces the same `ObjectDictionary` as the EDS parser, so converting is just:
+//! ```no_run
+//! let od = canopeners::xdd::load("device.xdd").unwrap();
+//! canopeners::eds::save(&od, "device.eds").unwrap();
+//! ```
+//! Covers the device profile (identity, `ApplicationProcess` parameters) and the communication
+//! network profile (`CANopenObjectList`, baud rates, NMT features, `deviceCommissioning` of XDCs).
+//! `CANopenObject`s referring to a parameter (`uniqueIDRef`) take missing attributes from it.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use roxmltree::{Document, Node};
+
+use crate::enums::{AccessType, DataType, ObjectType};
+use crate::od::{
+    parse_integer, DeviceCommissioning, DeviceInfo, FileInfo, Object, ObjectDictionary, ValueExpr,
+    Variable,
+};
+use crate::CanOpenError;
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|c| c.is_element() && c.tag_name().name() == name)
+}
+
+fn descendant<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.descendants()
+        .find(|c| c.is_element() && c.tag_name().name() == name)
+}
+
+fn text(node: Node, name: &str) -> String {
+    child(node, name)
+        .and_then(|c| c.text())
+        .unwrap_or_default()
+        .trim()
+        .to_owned()
+}
+
+/// Indices, sub-indices and data types are hex, with or without 0x
+fn parse_hex(s: &str) -> Option<u32> {
+    let s = s.trim();
+    let digits = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    u32::from_str_radix(digits, 16).ok()
+}
+
+fn parse_bool(s: &str) -> bool {
+    matches!(s.trim(), "true" | "1")
+}
+
+/// eg. "250 Kbps", "1000 Kbps"
+fn parse_baud_rate(s: &str) -> Option<u16> {
+    s.trim().strip_suffix("Kbps")?.trim().parse().ok()
+}
+
+/// IEC 61131 types used by `ApplicationProcess` parameters
+fn iec_data_type(name: &str) -> Option<DataType> {
+    Some(match name {
+        "BOOL" => DataType::Boolean,
+        "SINT" => DataType::Integer8,
+        "INT" => DataType::Integer16,
+        "DINT" => DataType::Integer32,
+        "LINT" => DataType::Integer64,
+        "USINT" | "BYTE" => DataType::Unsigned8,
+        "UINT" | "WORD" => DataType::Unsigned16,
+        "UDINT" | "DWORD" => DataType::Unsigned32,
+        "ULINT" | "LWORD" => DataType::Unsigned64,
+        "REAL" => DataType::Real32,
+        "LREAL" => DataType::Real64,
+        "STRING" => DataType::VisibleString,
+        "WSTRING" => DataType::UnicodeString,
+        "BITSTRING" => DataType::OctetString,
+        _ => return None,
+    })
+}
+
+/// `ApplicationProcess` parameter, only what `CANopenObject`s may inherit
+#[derive(Default)]
+struct Parameter<'a> {
+    data_type: Option<DataType>,
+    access_type: Option<AccessType>,
+    default_value: Option<&'a str>,
+    actual_value: Option<&'a str>,
+    low_limit: Option<&'a str>,
+    high_limit: Option<&'a str>,
+}
+
+struct Parser<'a, 'input> {
+    doc: &'a Document<'input>,
+    parameters: HashMap<&'a str, Parameter<'a>>,
+}
+
+impl<'a, 'input> Parser<'a, 'input> {
+    fn error(&self, node: Node, message: impl Into<String>) -> CanOpenError {
+        CanOpenError::XddError {
+            line: self.doc.text_pos_at(node.range().start).row as usize,
+            message: message.into(),
+        }
+    }
+
+    fn required(&self, node: Node<'a, 'input>, name: &str) -> Result<&'a str, CanOpenError> {
+        node.attribute(name).ok_or_else(|| {
+            self.error(
+                node,
+                format!("<{}> is missing {name}", node.tag_name().name()),
+            )
+        })
+    }
+
+    fn hex_attribute(
+        &self,
+        node: Node<'a, 'input>,
+        name: &str,
+    ) -> Result<Option<u32>, CanOpenError> {
+        node.attribute(name)
+            .map(|v| {
+                parse_hex(v).ok_or_else(|| self.error(node, format!("{name}={v:?} is not hex")))
+            })
+            .transpose()
+    }
+
+    fn integer_attribute<T: TryFrom<i128>>(
+        &self,
+        node: Node<'a, 'input>,
+        name: &str,
+    ) -> Result<Option<T>, CanOpenError> {
+        node.attribute(name)
+            .map(|v| {
+                parse_integer(v)
+                    .and_then(|i| T::try_from(i).ok())
+                    .ok_or_else(|| self.error(node, format!("{name}={v:?} is not a valid number")))
+            })
+            .transpose()
+    }
+
+    fn parse_parameter(&self, node: Node<'a, 'input>) -> Result<Parameter<'a>, CanOpenError> {
+        let access_type = match node.attribute("access") {
+            None => None,
+            Some("const") => Some(AccessType::Const),
+            Some("read") => Some(AccessType::ReadOnly),
+            Some("write") => Some(AccessType::WriteOnly),
+            Some("readWrite") => Some(AccessType::ReadWrite),
+            // inputs are written by the network (RPDO), outputs read by it (TPDO)
+            Some("readWriteInput") => Some(AccessType::ReadWriteWrite),
+            Some("readWriteOutput") => Some(AccessType::ReadWriteRead),
+            Some("noAccess") => None,
+            Some(other) => {
+                return Err(self.error(node, format!("unknown parameter access {other:?}")))
+            }
+        };
+        let value = |name| child(node, name).and_then(|c| c.attribute("value"));
+        let range = child(node, "allowedValues").and_then(|a| child(a, "range"));
+        let limit = |name| {
+            range
+                .and_then(|r| child(r, name))
+                .and_then(|c| c.attribute("value"))
+        };
+        Ok(Parameter {
+            data_type: node
+                .children()
+                .filter(|c| c.is_element())
+                .find_map(|c| iec_data_type(c.tag_name().name())),
+            access_type,
+            default_value: value("defaultValue"),
+            actual_value: value("actualValue"),
+            low_limit: limit("minValue"),
+            high_limit: limit("maxValue"),
+        })
+    }
+
+    /// `CANopenObject` (VAR/DOMAIN) or `CANopenSubObject`
+    fn parse_variable(
+        &self,
+        node: Node<'a, 'input>,
+        index: u16,
+        sub_index: u8,
+        object_type: ObjectType,
+    ) -> Result<Variable, CanOpenError> {
+        let name = self.required(node, "name")?;
+        let parameter = node
+            .attribute("uniqueIDRef")
+            .and_then(|id| self.parameters.get(id));
+        let inherited = |f: fn(&Parameter<'a>) -> Option<&'a str>| parameter.and_then(f);
+
+        let data_type = match self.hex_attribute(node, "dataType")? {
+            Some(data_type) => DataType::decode(data_type as u16),
+            None => match parameter.and_then(|p| p.data_type) {
+                Some(data_type) => data_type,
+                None if object_type == ObjectType::Domain => DataType::Domain,
+                None => return Err(self.error(node, format!("{name} has no dataType"))),
+            },
+        };
+        let access_type = match node.attribute("accessType") {
+            Some(access) => {
+                AccessType::decode(access).map_err(|e| self.error(node, e.to_string()))?
+            }
+            None => match parameter.and_then(|p| p.access_type) {
+                Some(access_type) => access_type,
+                None if object_type == ObjectType::Domain => AccessType::ReadWrite,
+                None => return Err(self.error(node, format!("{name} has no accessType"))),
+            },
+        };
+        let mut variable = Variable::new(index, sub_index, name, data_type, access_type);
+        let value = |attribute, fallback| {
+            node.attribute(attribute)
+                .or_else(|| inherited(fallback))
+                .filter(|v| !v.is_empty())
+                .map(ValueExpr::new)
+        };
+        variable.default_value = value("defaultValue", |p| p.default_value);
+        variable.parameter_value = value("actualValue", |p| p.actual_value);
+        variable.low_limit = value("lowLimit", |p| p.low_limit);
+        variable.high_limit = value("highLimit", |p| p.high_limit);
+        variable.pdo_mapping = node.attribute("PDOmapping").is_some_and(|m| m != "no");
+        Ok(variable)
+    }
+
+    fn parse_object(&self, node: Node<'a, 'input>) -> Result<Object, CanOpenError> {
+        let index = self
+            .hex_attribute(node, "index")?
+            .and_then(|i| u16::try_from(i).ok())
+            .ok_or_else(|| self.error(node, "CANopenObject without a valid index"))?;
+        let name = self.required(node, "name")?;
+        let object_type = match self.integer_attribute::<u8>(node, "objectType")? {
+            None => ObjectType::Var,
+            Some(code) => ObjectType::decode(code).map_err(|e| self.error(node, e.to_string()))?,
+        };
+        let mut object = Object::new(index, name, object_type);
+        match object_type {
+            ObjectType::Var | ObjectType::DefType | ObjectType::Domain => {
+                object.insert(self.parse_variable(node, index, 0, object_type)?);
+            }
+            ObjectType::Null => {}
+            _ => {
+                for sub in node
+                    .children()
+                    .filter(|c| c.is_element() && c.tag_name().name() == "CANopenSubObject")
+                {
+                    let sub_index = self
+                        .hex_attribute(sub, "subIndex")?
+                        .and_then(|i| u8::try_from(i).ok())
+                        .ok_or_else(|| {
+                            self.error(sub, "CANopenSubObject without a valid subIndex")
+                        })?;
+                    object.insert(self.parse_variable(sub, index, sub_index, ObjectType::Var)?);
+                }
+            }
+        }
+        Ok(object)
+    }
+}
+
+fn parse_file_info(body: Node, header: Option<Node>) -> FileInfo {
+    let attribute = |name| body.attribute(name).unwrap_or_default().to_owned();
+    FileInfo {
+        file_name: attribute("fileName"),
+        file_version: attribute("fileVersion"),
+        description: header.map(|h| text(h, "ProfileName")).unwrap_or_default(),
+        creation_time: attribute("fileCreationTime"),
+        creation_date: attribute("fileCreationDate"),
+        created_by: attribute("fileCreator"),
+        modification_time: attribute("fileModificationTime"),
+        modification_date: attribute("fileModificationDate"),
+        modified_by: attribute("fileModifiedBy"),
+        ..Default::default()
+    }
+}
+
+/// Parses an XDD/XDC file's contents
+pub fn parse(s: &str) -> Result<ObjectDictionary, CanOpenError> {
+    let doc = Document::parse(s).map_err(|e| CanOpenError::XddError {
+        line: e.pos().row as usize,
+        message: e.to_string(),
+    })?;
+    let mut parser = Parser {
+        doc: &doc,
+        parameters: HashMap::new(),
+    };
+    let root = doc.root_element();
+    let bodies: Vec<Node> = root
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "ProfileBody")
+        .collect();
+    let communication = bodies
+        .iter()
+        .copied()
+        .find(|b| descendant(*b, "CANopenObjectList").is_some())
+        .ok_or_else(|| parser.error(root, "no ProfileBody with a CANopenObjectList"))?;
+    let device = bodies
+        .iter()
+        .copied()
+        .find(|b| child(*b, "DeviceIdentity").is_some());
+
+    let mut od = ObjectDictionary::new();
+    // the device profile describes the device, fall back to the communication profile
+    let info_body = device.unwrap_or(communication);
+    let header = info_body
+        .parent()
+        .and_then(|profile| child(profile, "ProfileHeader"));
+    od.file_info = parse_file_info(info_body, header);
+
+    let mut device_info = DeviceInfo::default();
+    if let Some(device) = device {
+        let identity = child(device, "DeviceIdentity").unwrap();
+        device_info.vendor_name = text(identity, "vendorName");
+        device_info.vendor_number = parse_hex(&text(identity, "vendorID"));
+        device_info.product_name = text(identity, "productName");
+        device_info.product_number = parse_hex(&text(identity, "productID"));
+        device_info.order_code = text(identity, "orderNumber");
+        for parameter in device
+            .descendants()
+            .filter(|n| n.is_element() && n.tag_name().name() == "parameter")
+        {
+            if let Some(id) = parameter.attribute("uniqueID") {
+                let parsed = parser.parse_parameter(parameter)?;
+                parser.parameters.insert(id, parsed);
+            }
+        }
+    }
+    for rate in communication
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "supportedBaudRate")
+    {
+        if let Some(rate) = rate.attribute("value").and_then(parse_baud_rate) {
+            device_info.baud_rates.push(rate);
+        }
+    }
+    device_info.baud_rates.sort_unstable();
+    device_info.baud_rates.dedup();
+    if let Some(features) = descendant(communication, "CANopenGeneralFeatures") {
+        let flag = |name| features.attribute(name).is_some_and(parse_bool);
+        device_info.simple_boot_up_slave = flag("bootUpSlave");
+        device_info.group_messaging = flag("groupMessaging");
+        device_info.lss_supported = flag("layerSettingServiceSlave");
+        device_info.granularity = parser
+            .integer_attribute(features, "granularity")?
+            .unwrap_or(0);
+        device_info.dynamic_channels_supported = parser
+            .integer_attribute(features, "dynamicChannels")?
+            .unwrap_or(0);
+        device_info.nr_of_rx_pdo = parser
+            .integer_attribute(features, "nrOfRxPDO")?
+            .unwrap_or(0);
+        device_info.nr_of_tx_pdo = parser
+            .integer_attribute(features, "nrOfTxPDO")?
+            .unwrap_or(0);
+    }
+    if let Some(features) = descendant(communication, "CANopenMasterFeatures") {
+        device_info.simple_boot_up_master =
+            features.attribute("bootUpMaster").is_some_and(parse_bool);
+    }
+    od.device_info = device_info;
+
+    if let Some(commissioning) = descendant(communication, "deviceCommissioning") {
+        od.device_commissioning = Some(DeviceCommissioning {
+            node_id: parser
+                .integer_attribute(commissioning, "nodeID")?
+                .ok_or_else(|| parser.error(commissioning, "deviceCommissioning without nodeID"))?,
+            node_name: commissioning
+                .attribute("nodeName")
+                .unwrap_or_default()
+                .to_owned(),
+            baudrate: commissioning
+                .attribute("actualBaudRate")
+                .and_then(parse_baud_rate)
+                .unwrap_or(0),
+            net_number: parser
+                .integer_attribute(commissioning, "networkNumber")?
+                .unwrap_or(0),
+            network_name: commissioning
+                .attribute("networkName")
+                .unwrap_or_default()
+                .to_owned(),
+            canopen_manager: commissioning
+                .attribute("CANopenManager")
+                .is_some_and(parse_bool),
+            lss_serial_number: None,
+        });
+    }
+
+    let objects = descendant(communication, "CANopenObjectList").unwrap();
+    for node in objects
+        .children()
+        .filter(|c| c.is_element() && c.tag_name().name() == "CANopenObject")
+    {
+        let object = parser.parse_object(node)?;
+        if od.get(object.index).is_some() {
+            return Err(parser.error(node, format!("duplicate object {:#06x}", object.index)));
+        }
+        od.insert(object);
+    }
+    // the device identity has no revision, it's only in the identity object
+    if let Some(revision) = od
+        .variable(0x1018, 3)
+        .and_then(|v| v.default_value.as_ref())
+    {
+        od.device_info.revision_number =
+            revision.to_integer(0).ok().and_then(|r| r.try_into().ok());
+    }
+    Ok(od)
+}
+
+/// Reads and parses an XDD/XDC file
+pub fn load(path: impl AsRef<Path>) -> Result<ObjectDictionary, CanOpenError> {
+    let s = std::fs::read_to_string(path).map_err(CanOpenError::IOError)?;
+    parse(&s)
+}
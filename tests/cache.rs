@@ -0,0 +1,81 @@
+mod common;
+
+use canopeners::cache::SdoCache;
+use canopeners::enums::{AccessType, DataType, ObjectType};
+use canopeners::od::{Object, ObjectDictionary, Variable};
+use canopeners::{CanOpenError, Conn, Guard, GuardStatus, Message, Nmt, NmtFunction};
+use common::SdoServer;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::time::Duration;
+
+const NODE: u8 = 0x47;
+
+fn description() -> ObjectDictionary {
+    let mut od = ObjectDictionary::new();
+    for (index, access_type) in [
+        (0x1000, AccessType::Const),
+        (0x1008, AccessType::ReadOnly),
+        (0x1017, AccessType::ReadWrite),
+    ] {
+        let mut object = Object::new(index, format!("Object {index:#06x}"), ObjectType::Var);
+        object.insert(Variable::new(
+            index,
+            0,
+            format!("Object {index:#06x}"),
+            DataType::Unsigned32,
+            access_type,
+        ));
+        od.insert(object);
+    }
+    od
+}
+
+fn offline(result: Result<impl std::fmt::Debug, CanOpenError>) -> bool {
+    matches!(result, Err(CanOpenError::Timeout(_)))
+}
+
+#[test]
+fn caches_const_and_read_only() {
+    let done = AtomicBool::new(false);
+    let mut cache = SdoCache::new().with_description(description());
+    let mut conn = Conn::new("vcan0").unwrap();
+    conn.set_sdo_timeout(Duration::from_millis(50));
+    std::thread::scope(|s| {
+        let server = s.spawn(|| {
+            SdoServer::new(NODE)
+                .with(0x1000, 0, &0x20192u32.to_le_bytes())
+                .with(0x1008, 0, b"drive")
+                .with(0x1017, 0, &100u32.to_le_bytes())
+                .run(&done)
+        });
+        std::thread::sleep(Duration::from_millis(20));
+        let device_type: u32 = cache.read_typed(&mut conn, NODE, 0x1000, 0).unwrap();
+        assert_eq!(device_type, 0x20192);
+        assert_eq!(&*cache.read(&mut conn, NODE, 0x1008, 0).unwrap(), b"drive");
+        assert_eq!(
+            cache.read_typed::<u32>(&mut conn, NODE, 0x1017, 0).unwrap(),
+            100
+        );
+        done.store(true, SeqCst);
+        server.join().unwrap();
+    });
+
+    // the node is gone, cached values still come back
+    assert_eq!(
+        cache.read_typed::<u32>(&mut conn, NODE, 0x1000, 0).unwrap(),
+        0x20192
+    );
+    assert_eq!(cache.cached(NODE, 0x1008, 0), Some(&b"drive"[..]));
+    assert!(offline(cache.read(&mut conn, NODE, 0x1017, 0)));
+    assert!(offline(cache.read_uncached(&mut conn, NODE, 0x1000, 0)));
+
+    cache.process(&Message::Nmt(Nmt::new(
+        NmtFunction::ResetCommunication,
+        NODE + 1,
+    )));
+    assert!(cache.cached(NODE, 0x1000, 0).is_some());
+    cache.process(&Message::Guard(Guard::new(NODE, false, GuardStatus::Boot)));
+    assert!(cache.cached(NODE, 0x1000, 0).is_none());
+    assert!(cache.cached(NODE, 0x1008, 0).is_none());
+    assert!(offline(cache.read(&mut conn, NODE, 0x1000, 0)));
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use canopeners::{codegen, eds, CanOpenError, Conn};
+use common::SdoServer;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+
+#[allow(dead_code)]
+mod drive {
+    include!("generated/drive.rs");
+}
+
+const EDS: &str = r#"
+[FileInfo]
+FileName=drive.eds
+
+[1000]
+ParameterName=Device type
+DataType=0x0007
+AccessType=ro
+
+[1008]
+ParameterName=Manufacturer device name
+DataType=0x0009
+AccessType=const
+
+[1018]
+ParameterName=Identity
+ObjectType=0x9
+
+[1018sub0]
+ParameterName=Highest sub-index supported
+DataType=0x0005
+AccessType=const
+
+[1018sub1]
+ParameterName=VendorID
+DataType=0x0007
+AccessType=ro
+
+[2000]
+ParameterName=Type
+DataType=0x0004
+AccessType=rww
+
+[2001]
+ParameterName=Firmware
+ObjectType=0x2
+DataType=0x000F
+
+[6040]
+ParameterName=Controlword
+DataType=0x0006
+AccessType=rw
+
+[6060]
+ParameterName=Modes of operation
+DataType=0x0002
+AccessType=wo
+"#;
+
+#[test]
+fn generates_accessors() {
+    let od = eds::parse(EDS).unwrap();
+    assert_eq!(codegen::generate(&od), include_str!("generated/drive.rs"));
+}
+
+#[test]
+fn typed_read_write() {
+    let node = 0x16;
+    let server = SdoServer::new(node)
+        .with_read_only(0x1008, 0, b"drive\0\0")
+        .with_read_only(0x1018, 1, &0x1234u32.to_le_bytes())
+        .with(0x6040, 0, &[0, 0])
+        .with(0x6060, 0, &[0]);
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| server.run(&done));
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        assert_eq!(
+            drive::manufacturer_device_name()
+                .read(&mut conn, node)
+                .unwrap(),
+            "drive"
+        );
+        assert_eq!(
+            drive::IDENTITY_VENDOR_ID.read(&mut conn, node).unwrap(),
+            0x1234
+        );
+        drive::controlword().write(&mut conn, node, 0x0F).unwrap();
+        assert_eq!(drive::controlword().read(&mut conn, node).unwrap(), 0x0F);
+        drive::modes_of_operation()
+            .write(&mut conn, node, -1)
+            .unwrap();
+        assert!(matches!(
+            drive::type_().read(&mut conn, node),
+            Err(CanOpenError::SdoAbortTransfer { .. })
+        ));
+
+        done.store(true, SeqCst);
+        let server = server.join().unwrap();
+        assert_eq!(
+            server.writes,
+            vec![(0x6040, 0, vec![0x0F, 0]), (0x6060, 0, vec![0xFF]),]
+        );
+    });
+}
+
+#[test]
+fn keywords_are_escaped() {
+    let od = eds::parse(
+        r#"
+[2100]
+ParameterName=While
+DataType=0x0005
+AccessType=rw
+
+[2101]
+ParameterName=Try
+DataType=0x0005
+AccessType=rw
+"#,
+    )
+    .unwrap();
+    let generated = codegen::generate(&od);
+    assert!(generated.contains("pub const fn while_()"));
+    assert!(generated.contains("pub const fn try_()"));
+}
@@ -0,0 +1,164 @@
+// shared by several test files, each only uses part of it
+#![allow(dead_code)]
+
+use canopeners::enums::AbortCode;
+use canopeners::{
+    Conn, Message, ReqRes, Sdo, SdoCmd, SdoCmdAbortTransfer, SdoCmdDownloadSegmentTx,
+    SdoCmdInitiateDownloadTx, SdoCmdInitiatePayload, SdoCmdInitiateUploadTx, SdoCmdUploadSegmentTx,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::time::Duration;
+
+/// bare bones SDO server, with expedited and segmented transfers
+#[derive(Debug, Default)]
+pub struct SdoServer {
+    pub node_id: u8,
+    pub objects: BTreeMap<(u16, u8), Vec<u8>>,
+    pub read_only: BTreeSet<(u16, u8)>,
+    /// entries that are refused with the given abort code
+    pub aborts: BTreeMap<(u16, u8), AbortCode>,
+    /// every successful write, in order
+    pub writes: Vec<(u16, u8, Vec<u8>)>,
+    upload: Option<Vec<u8>>,
+    download: Option<(u16, u8, Vec<u8>)>,
+}
+
+impl SdoServer {
+    pub fn new(node_id: u8) -> Self {
+        Self {
+            node_id,
+            ..Default::default()
+        }
+    }
+
+    pub fn with(mut self, index: u16, sub_index: u8, data: &[u8]) -> Self {
+        self.objects.insert((index, sub_index), data.to_vec());
+        self
+    }
+
+    pub fn with_read_only(mut self, index: u16, sub_index: u8, data: &[u8]) -> Self {
+        self.read_only.insert((index, sub_index));
+        self.with(index, sub_index, data)
+    }
+
+    pub fn with_abort(mut self, index: u16, sub_index: u8, abort_code: AbortCode) -> Self {
+        self.aborts.insert((index, sub_index), abort_code);
+        self
+    }
+
+    fn abort(index: u16, sub_index: u8, abort_code: AbortCode) -> SdoCmd {
+        SdoCmd::AbortTransfer(SdoCmdAbortTransfer {
+            index,
+            sub_index,
+            abort_code,
+        })
+    }
+
+    fn store(&mut self, index: u16, sub_index: u8, data: Vec<u8>) -> SdoCmd {
+        if !self.objects.contains_key(&(index, sub_index)) {
+            return Self::abort(index, sub_index, AbortCode::ObjectNotInDictionary);
+        }
+        if self.read_only.contains(&(index, sub_index)) {
+            return Self::abort(index, sub_index, AbortCode::AttemptToWriteReadOnlyObject);
+        }
+        self.objects.insert((index, sub_index), data.clone());
+        self.writes.push((index, sub_index, data));
+        SdoCmd::InitiateDownloadTx(SdoCmdInitiateDownloadTx { index, sub_index })
+    }
+
+    pub fn handle(&mut self, command: SdoCmd) -> Option<SdoCmd> {
+        let refused = match &command {
+            SdoCmd::InitiateUploadRx(req) => Some((req.index, req.sub_index)),
+            SdoCmd::InitiateDownloadRx(req) => Some((req.index, req.sub_index)),
+            _ => None,
+        };
+        if let Some((index, sub_index)) = refused {
+            if let Some(abort_code) = self.aborts.get(&(index, sub_index)) {
+                return Some(Self::abort(index, sub_index, *abort_code));
+            }
+        }
+        Some(match command {
+            SdoCmd::InitiateUploadRx(req) => match self.objects.get(&(req.index, req.sub_index)) {
+                None => Self::abort(req.index, req.sub_index, AbortCode::ObjectNotInDictionary),
+                Some(data) if data.len() <= 4 => SdoCmd::InitiateUploadTx(SdoCmdInitiateUploadTx {
+                    index: req.index,
+                    sub_index: req.sub_index,
+                    payload: SdoCmdInitiatePayload::Expedited(data.clone().into()),
+                }),
+                Some(data) => {
+                    self.upload = Some(data.clone());
+                    SdoCmd::InitiateUploadTx(SdoCmdInitiateUploadTx {
+                        index: req.index,
+                        sub_index: req.sub_index,
+                        payload: SdoCmdInitiatePayload::Segmented(Some(data.len() as u32)),
+                    })
+                }
+            },
+            SdoCmd::UploadSegmentRx(req) => {
+                let remaining = self.upload.as_mut()?;
+                let data: Vec<u8> = remaining.drain(..remaining.len().min(7)).collect();
+                let last = remaining.is_empty();
+                if last {
+                    self.upload = None;
+                }
+                SdoCmd::UploadSegmentTx(SdoCmdUploadSegmentTx {
+                    toggle: req.toggle,
+                    data: data.into(),
+                    last,
+                })
+            }
+            SdoCmd::InitiateDownloadRx(req) => match req.payload {
+                SdoCmdInitiatePayload::Expedited(data) => {
+                    self.store(req.index, req.sub_index, data.to_vec())
+                }
+                SdoCmdInitiatePayload::Segmented(_) => {
+                    self.download = Some((req.index, req.sub_index, Vec::new()));
+                    SdoCmd::InitiateDownloadTx(SdoCmdInitiateDownloadTx {
+                        index: req.index,
+                        sub_index: req.sub_index,
+                    })
+                }
+            },
+            SdoCmd::DownloadSegmentRx(req) => {
+                let (index, sub_index, mut data) = self.download.take()?;
+                data.extend_from_slice(&req.data);
+                if req.last {
+                    if let SdoCmd::AbortTransfer(abort) = self.store(index, sub_index, data) {
+                        return Some(SdoCmd::AbortTransfer(abort));
+                    }
+                } else {
+                    self.download = Some((index, sub_index, data));
+                }
+                SdoCmd::DownloadSegmentTx(SdoCmdDownloadSegmentTx { toggle: req.toggle })
+            }
+            _ => return None,
+        })
+    }
+
+    /// serve requests on vcan0 until `done`
+    pub fn run(self, done: &AtomicBool) -> Self {
+        self.run_on(Conn::new("vcan0").unwrap(), done)
+    }
+
+    /// like `run`, on a connection that may have SDO channels set up
+    pub fn run_on(mut self, conn: Conn, done: &AtomicBool) -> Self {
+        while !done.load(SeqCst) {
+            let Ok(Message::Sdo(sdo)) = conn.recv_timeout(Duration::from_millis(5)) else {
+                continue;
+            };
+            if sdo.node_id != self.node_id || !matches!(sdo.reqres, ReqRes::Req) {
+                continue;
+            }
+            if let Some(command) = self.handle(sdo.command) {
+                conn.send(&Message::Sdo(Sdo {
+                    node_id: self.node_id,
+                    reqres: ReqRes::Res,
+                    command,
+                }))
+                .unwrap();
+            }
+        }
+        self
+    }
+}
@@ -0,0 +1,193 @@
+mod common;
+
+use canopeners::enums::AbortCode;
+use canopeners::od::ValueExpr;
+use canopeners::{dcf, eds, Conn};
+use common::SdoServer;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+
+const EDS: &str = r#"
+[FileInfo]
+FileName=widget.eds
+EDSVersion=4.0
+
+[DeviceInfo]
+VendorName=ACME
+ProductName=Widget
+
+[1000]
+ParameterName=Device type
+DataType=0x0007
+AccessType=ro
+
+[1008]
+ParameterName=Device name
+DataType=0x0009
+AccessType=const
+
+[1017]
+ParameterName=Producer heartbeat time
+DataType=0x0006
+AccessType=rw
+DefaultValue=0
+
+[1800]
+ParameterName=TPDO1 communication parameter
+ObjectType=0x9
+
+[1800sub0]
+ParameterName=Highest sub-index supported
+DataType=0x0005
+AccessType=const
+DefaultValue=1
+
+[1800sub1]
+ParameterName=COB-ID
+DataType=0x0007
+AccessType=rw
+DefaultValue=$NODEID+0x180
+
+[1A00]
+ParameterName=TPDO1 mapping parameter
+ObjectType=0x9
+
+[1A00sub0]
+ParameterName=Number of mapped objects
+DataType=0x0005
+AccessType=rw
+
+[1A00sub1]
+ParameterName=Mapped object 1
+DataType=0x0007
+AccessType=rw
+
+[2000]
+ParameterName=Gain
+DataType=0x0003
+AccessType=rw
+"#;
+
+#[test]
+fn dcf_text_round_trip() {
+    let mut od = eds::parse(EDS).unwrap();
+    od.device_commissioning = Some(canopeners::od::DeviceCommissioning {
+        node_id: 0x15,
+        node_name: "widget".to_owned(),
+        baudrate: 500,
+        ..Default::default()
+    });
+    od.variable_mut(0x2000, 0).unwrap().parameter_value = Some(ValueExpr::new("-12"));
+
+    let text = eds::write(&od);
+    assert!(text.contains("[DeviceComissioning]"));
+    assert!(text.contains("ParameterValue=-12"));
+    assert_eq!(dcf::parse(&text).unwrap(), od);
+}
+
+#[test]
+fn snapshot_and_restore() {
+    let node = 0x15;
+    let server = SdoServer::new(node)
+        .with_read_only(0x1000, 0, &0x0002_0192u32.to_le_bytes())
+        .with_read_only(0x1008, 0, b"widget-9000")
+        .with(0x1017, 0, &100u16.to_le_bytes())
+        .with_read_only(0x1800, 0, &[1])
+        .with(0x1800, 1, &0x195u32.to_le_bytes())
+        .with(0x1A00, 0, &[1])
+        .with(0x1A00, 1, &0x6000_0108u32.to_le_bytes());
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| server.run(&done));
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        let eds = eds::parse(EDS).unwrap();
+        let (mut snapshot, failed) = dcf::read_from_node(&mut conn, node, &eds).unwrap();
+        let value = |od: &canopeners::od::ObjectDictionary, index, sub_index| {
+            od.variable(index, sub_index)
+                .unwrap()
+                .parameter_value
+                .as_ref()
+                .map(|v| v.as_str().to_owned())
+        };
+        assert_eq!(value(&snapshot, 0x1000, 0).as_deref(), Some("0x20192"));
+        assert_eq!(value(&snapshot, 0x1008, 0).as_deref(), Some("widget-9000"));
+        assert_eq!(value(&snapshot, 0x1017, 0).as_deref(), Some("0x64"));
+        assert_eq!(value(&snapshot, 0x2000, 0), None);
+        assert_eq!(
+            failed,
+            vec![dcf::FailedEntry {
+                index: 0x2000,
+                sub_index: 0,
+                failure: dcf::Failure::Aborted(AbortCode::ObjectNotInDictionary),
+            }]
+        );
+        assert_eq!(
+            snapshot.device_commissioning.as_ref().unwrap().node_id,
+            node
+        );
+
+        snapshot.variable_mut(0x1017, 0).unwrap().parameter_value = Some(ValueExpr::new("500"));
+        snapshot.variable_mut(0x1800, 1).unwrap().parameter_value =
+            Some(ValueExpr::new("$NODEID+0x280"));
+        snapshot.variable_mut(0x2000, 0).unwrap().parameter_value = Some(ValueExpr::new("3"));
+        let failed = dcf::write_to_node(&mut conn, node, &snapshot).unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].index, 0x2000);
+
+        done.store(true, SeqCst);
+        let server = server.join().unwrap();
+        assert_eq!(
+            server.writes,
+            vec![
+                (0x1800, 1, 0x8000_0295u32.to_le_bytes().to_vec()),
+                (0x1A00, 0, vec![0]),
+                (0x1017, 0, 500u16.to_le_bytes().to_vec()),
+                (0x1A00, 1, 0x6000_0108u32.to_le_bytes().to_vec()),
+                (0x1A00, 0, vec![1]),
+                (0x1800, 1, 0x295u32.to_le_bytes().to_vec()),
+            ]
+        );
+    });
+}
+
+#[test]
+fn wrong_size_is_not_an_abort() {
+    let node = 0x4A;
+    // 4 bytes for an Unsigned16
+    let server = SdoServer::new(node).with(0x1017, 0, &100u32.to_le_bytes());
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| server.run(&done));
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        let eds = eds::parse(
+            r#"
+[1017]
+ParameterName=Producer heartbeat time
+DataType=0x0006
+AccessType=rw
+"#,
+        )
+        .unwrap();
+        let (snapshot, failed) = dcf::read_from_node(&mut conn, node, &eds).unwrap();
+        assert_eq!(
+            failed,
+            vec![dcf::FailedEntry {
+                index: 0x1017,
+                sub_index: 0,
+                failure: dcf::Failure::WrongSize { len: 4 },
+            }]
+        );
+        assert_eq!(failed[0].failure.to_string(), "wrong size, got 4 bytes");
+        assert!(snapshot
+            .variable(0x1017, 0)
+            .unwrap()
+            .parameter_value
+            .is_none());
+
+        done.store(true, SeqCst);
+        server.join().unwrap();
+    });
+}
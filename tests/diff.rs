@@ -0,0 +1,147 @@
+mod common;
+
+use canopeners::diff::{self, DiffEntry, Difference};
+use canopeners::od::ValueExpr;
+use canopeners::{dcf, Conn};
+use common::SdoServer;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+
+const DCF: &str = r#"
+[1000]
+ParameterName=Device type
+DataType=0x0007
+AccessType=ro
+DefaultValue=0x00020192
+
+[1017]
+ParameterName=Producer heartbeat time
+DataType=0x0006
+AccessType=rw
+DefaultValue=0
+ParameterValue=100
+
+[1800]
+ParameterName=TPDO1 communication parameter
+ObjectType=0x9
+
+[1800sub1]
+ParameterName=COB-ID
+DataType=0x0007
+AccessType=rw
+DefaultValue=$NODEID+0x180
+
+[2000]
+ParameterName=Gain
+DataType=0x0003
+AccessType=rw
+DefaultValue=5
+
+[2001]
+ParameterName=Offset
+DataType=0x0003
+AccessType=rw
+
+[2002]
+ParameterName=Scale
+DataType=0x0003
+AccessType=rw
+"#;
+
+fn device(node: u8, heartbeat: u16) -> SdoServer {
+    SdoServer::new(node)
+        .with(0x1000, 0, &0x0002_0192u32.to_le_bytes())
+        .with(0x1017, 0, &heartbeat.to_le_bytes())
+        .with(0x1800, 1, &(0x180 + node as u32).to_le_bytes())
+        .with_read_only(0x2001, 0, &[0; 2])
+}
+
+fn changed(index: u16, node_id: u8, expected: &str, actual: &str, name: &str) -> DiffEntry {
+    DiffEntry {
+        index,
+        sub_index: 0,
+        name: name.to_owned(),
+        node_id,
+        difference: Difference::Changed {
+            expected: ValueExpr::new(expected),
+            actual: ValueExpr::new(actual),
+        },
+    }
+}
+
+#[test]
+fn diffs_against_reference_and_other_node() {
+    let (a, b) = (0x1A, 0x1B);
+    // a sends 4 bytes for an Integer16
+    let server_a = device(a, 200).with(0x2002, 0, &[1, 0, 0, 0]);
+    let server_b = device(b, 100)
+        .with(0x2000, 0, &5u16.to_le_bytes())
+        .with(0x2002, 0, &[1, 0]);
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server_a = s.spawn(|| server_a.run(&done));
+        let server_b = s.spawn(|| server_b.run(&done));
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        let reference = dcf::parse(DCF).unwrap();
+        let differences = diff::against_reference(&mut conn, a, &reference).unwrap();
+        assert_eq!(
+            differences,
+            vec![
+                changed(0x1017, a, "0x64", "0xC8", "Producer heartbeat time"),
+                DiffEntry {
+                    index: 0x2000,
+                    sub_index: 0,
+                    name: "Gain".to_owned(),
+                    node_id: a,
+                    difference: Difference::Missing,
+                },
+                DiffEntry {
+                    index: 0x2002,
+                    sub_index: 0,
+                    name: "Scale".to_owned(),
+                    node_id: a,
+                    difference: Difference::WrongSize { len: 4 },
+                },
+            ]
+        );
+        assert_eq!(
+            differences[0].to_string(),
+            "node 26 0x1017sub0 (Producer heartbeat time): expected 0x64, got 0xC8"
+        );
+        assert_eq!(
+            differences[2].to_string(),
+            "node 26 0x2002sub0 (Scale): wrong size, got 4 bytes"
+        );
+        assert!(diff::against_reference(&mut conn, b, &reference)
+            .unwrap()
+            .is_empty());
+
+        // COB-IDs differ by the node id only
+        let differences = diff::between_nodes(&mut conn, a, b, &reference).unwrap();
+        assert_eq!(
+            differences,
+            vec![
+                changed(0x1017, b, "0xC8", "0x64", "Producer heartbeat time"),
+                DiffEntry {
+                    index: 0x2000,
+                    sub_index: 0,
+                    name: "Gain".to_owned(),
+                    node_id: a,
+                    difference: Difference::Missing,
+                },
+                DiffEntry {
+                    index: 0x2002,
+                    sub_index: 0,
+                    name: "Scale".to_owned(),
+                    node_id: a,
+                    difference: Difference::WrongSize { len: 4 },
+                },
+            ]
+        );
+
+        done.store(true, SeqCst);
+        server_a.join().unwrap();
+        server_b.join().unwrap();
+    });
+}
@@ -0,0 +1,161 @@
+mod common;
+
+use canopeners::enums::{AbortCode, DataType, ObjectType};
+use canopeners::{dump, eds, Conn};
+use common::SdoServer;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+
+const EDS: &str = r#"
+[1000]
+ParameterName=Device type
+DataType=0x0007
+AccessType=ro
+
+[1003]
+ParameterName=Pre-defined error field
+ObjectType=0x8
+
+[1003sub0]
+ParameterName=Number of errors
+DataType=0x0005
+AccessType=rw
+
+[1003sub1]
+ParameterName=Standard error field
+DataType=0x0007
+AccessType=ro
+
+[1010]
+ParameterName=Store parameters
+DataType=0x0007
+AccessType=wo
+
+[1800]
+ParameterName=TPDO communication parameter
+ObjectType=0x9
+
+[1800sub0]
+ParameterName=Highest sub-index supported
+DataType=0x0005
+AccessType=const
+
+[1800sub1]
+ParameterName=COB-ID
+DataType=0x0007
+AccessType=rw
+
+[1800sub5]
+ParameterName=Event timer
+DataType=0x0006
+AccessType=rw
+
+[2000]
+ParameterName=Gain
+DataType=0x0003
+AccessType=rw
+"#;
+
+fn server(node: u8) -> SdoServer {
+    SdoServer::new(node)
+        .with(0x1000, 0, &0x0002_0192u32.to_le_bytes())
+        .with(0x1003, 0, &[2])
+        .with(0x1003, 1, &0x1000u32.to_le_bytes())
+        .with(0x1003, 2, &0x8110u32.to_le_bytes())
+        .with(0x1010, 0, &[0; 4])
+        .with(0x1800, 0, &[5])
+        .with(0x1800, 1, &0x180u32.to_le_bytes())
+        .with(0x1800, 5, &[10, 0])
+}
+
+#[test]
+fn dumps_described_objects() {
+    let node = 0x18;
+    let server = server(node);
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| server.run(&done));
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        let dump = dump::from_description(&mut conn, node, &eds::parse(EDS).unwrap()).unwrap();
+        let read: Vec<_> = dump
+            .entries
+            .iter()
+            .map(|e| (e.index, e.sub_index, e.result.clone()))
+            .collect();
+        assert_eq!(
+            read,
+            vec![
+                (0x1000, 0, Ok(0x0002_0192u32.to_le_bytes().to_vec())),
+                (0x1003, 0, Ok(vec![2])),
+                (0x1003, 1, Ok(0x1000u32.to_le_bytes().to_vec())),
+                // not in the EDS, typed like sub-index 1
+                (0x1003, 2, Ok(0x8110u32.to_le_bytes().to_vec())),
+                (0x1800, 0, Ok(vec![5])),
+                (0x1800, 1, Ok(0x180u32.to_le_bytes().to_vec())),
+                // the gap (sub-indices 2 to 4) isn't made up
+                (0x1800, 5, Ok(vec![10, 0])),
+                (0x2000, 0, Err(AbortCode::ObjectNotInDictionary)),
+            ]
+        );
+        assert_eq!(dump.failed().count(), 1);
+
+        let json = dump.to_json();
+        assert!(json.contains(r#"{"index": "0x1003", "sub_index": 2, "name": "Pre-defined error field 2", "data_type": "Unsigned32", "value": "0x8110", "data": "10810000"},"#));
+        assert!(json.contains(r#"{"index": "0x2000", "sub_index": 0, "name": "Gain", "data_type": "Integer16", "abort_code": 100794368, "error": "object does not exist in the object dictionary"}"#));
+
+        let dcf = eds::parse(&eds::write(&dump.to_dcf())).unwrap();
+        assert_eq!(dcf.device_commissioning.as_ref().unwrap().node_id, node);
+        let value = |index, sub_index| {
+            dcf.variable(index, sub_index)
+                .and_then(|v| v.parameter_value.as_ref())
+                .map(|v| v.as_str().to_owned())
+        };
+        assert_eq!(value(0x1003, 2).as_deref(), Some("0x8110"));
+        assert_eq!(value(0x2000, 0), None);
+        assert!(dcf.variable(0x1800, 4).is_none());
+
+        done.store(true, SeqCst);
+        server.join().unwrap();
+    });
+}
+
+#[test]
+fn probes_without_description() {
+    let node = 0x19;
+    let server = server(node);
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| server.run(&done));
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        let dump = dump::probe(&mut conn, node, 0x1000..=0x1010).unwrap();
+        let read: Vec<_> = dump
+            .entries
+            .iter()
+            .map(|e| (e.index, e.sub_index))
+            .collect();
+        assert_eq!(
+            read,
+            vec![
+                (0x1000, 0),
+                (0x1003, 0),
+                (0x1003, 1),
+                (0x1003, 2),
+                (0x1010, 0)
+            ]
+        );
+        assert_eq!(
+            dump.description.get(0x1003).unwrap().object_type,
+            ObjectType::Record
+        );
+        assert_eq!(
+            dump.description.variable(0x1000, 0).unwrap().data_type,
+            DataType::Domain
+        );
+
+        done.store(true, SeqCst);
+        server.join().unwrap();
+    });
+}
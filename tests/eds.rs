@@ -0,0 +1,135 @@
+use canopeners::enums::{AccessType, DataType, ObjectType};
+use canopeners::{eds, CanOpenError};
+
+const EDS: &str = r#"
+; written by hand
+[FileInfo]
+FileName=test.eds
+FileVersion=1
+EDSVersion=4.0
+CreatedBy=canopeners
+
+[DeviceInfo]
+VendorName=ACME
+VendorNumber=0x1234
+ProductName=Widget
+BaudRate_250=1
+BaudRate_500=1
+NrOfTXPDO=4
+LSS_Supported=1
+
+[MandatoryObjects]
+SupportedObjects=2
+1=0x1000
+2=0x1018
+
+[OptionalObjects]
+SupportedObjects=2
+1=0x1400
+2=0x1F50
+
+[1000]
+ParameterName=Device type
+ObjectType=0x7
+DataType=0x0007
+AccessType=ro
+DefaultValue=0x00020192
+PDOMapping=0
+
+[1018]
+ParameterName=Identity
+ObjectType=0x9
+SubNumber=2
+
+[1018sub0]
+ParameterName=Highest sub-index supported
+ObjectType=0x7
+DataType=0x0005
+AccessType=ro
+DefaultValue=1
+
+[1018sub1]
+ParameterName=Vendor-ID
+ObjectType=0x7
+DataType=0x0007
+AccessType=RO
+DefaultValue=0x1234
+
+[1400]
+ParameterName=RPDO communication parameter
+ObjectType=0x8
+DataType=0x0007
+AccessType=rw
+DefaultValue=$NODEID+0x200
+CompactSubObj=2
+
+[1400Name]
+NrOfEntries=1
+2=Transmission type
+
+[1F50]
+ParameterName=Program data
+ObjectType=0x2
+"#;
+
+#[test]
+fn parses_objects() {
+    let od = eds::parse(EDS).unwrap();
+    assert_eq!(od.file_info.file_name, "test.eds");
+    assert_eq!(od.file_info.created_by, "canopeners");
+    assert_eq!(od.device_info.vendor_number, Some(0x1234));
+    assert_eq!(od.device_info.baud_rates, vec![250, 500]);
+    assert_eq!(od.device_info.nr_of_tx_pdo, 4);
+    assert!(od.device_info.lss_supported);
+
+    let device_type = od.variable(0x1000, 0).unwrap();
+    assert_eq!(device_type.data_type, DataType::Unsigned32);
+    assert_eq!(device_type.access_type, AccessType::ReadOnly);
+    assert_eq!(
+        device_type
+            .default_value
+            .as_ref()
+            .unwrap()
+            .to_integer(1)
+            .unwrap(),
+        0x0002_0192
+    );
+
+    let identity = od.get(0x1018).unwrap();
+    assert_eq!(identity.object_type, ObjectType::Record);
+    assert_eq!(identity.sub_objects.len(), 2);
+    assert_eq!(identity.sub_object(1).unwrap().name, "Vendor-ID");
+
+    // compact array, one name overridden
+    let rpdo = od.get(0x1400).unwrap();
+    assert_eq!(rpdo.sub_objects.len(), 3);
+    assert_eq!(
+        rpdo.sub_object(1).unwrap().name,
+        "RPDO communication parameter1"
+    );
+    assert_eq!(rpdo.sub_object(2).unwrap().name, "Transmission type");
+    let cob_id = rpdo.sub_object(1).unwrap().default_value.as_ref().unwrap();
+    assert!(cob_id.is_node_id_relative());
+    assert_eq!(cob_id.to_integer(0x05).unwrap(), 0x205);
+
+    // domains default to rw
+    let program = od.variable(0x1F50, 0).unwrap();
+    assert_eq!(program.data_type, DataType::Domain);
+    assert_eq!(program.access_type, AccessType::ReadWrite);
+}
+
+#[test]
+fn errors_point_at_the_line() {
+    let bad_access = EDS.replace("AccessType=RO", "AccessType=readonly");
+    let line = EDS.lines().position(|l| l == "AccessType=RO").unwrap() + 1;
+    assert!(matches!(
+        eds::parse(&bad_access),
+        Err(CanOpenError::EdsError { line: l, .. }) if l == line
+    ));
+
+    let missing_object = EDS.replace("2=0x1F50", "2=0x1F51");
+    assert!(matches!(
+        eds::parse(&missing_object),
+        Err(CanOpenError::EdsError { message, .. }) if message.contains("0x1f51")
+    ));
+}
@@ -0,0 +1,123 @@
+use canopeners::emcy::EmergencyConsumer;
+use canopeners::enums::{EmergencyErrorClass, EmergencyErrorCode, EmergencyErrorRegister};
+use canopeners::{Emergency, Message};
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use std::sync::Arc;
+
+fn emcy(node_id: u8, code: EmergencyErrorCode, register: Vec<EmergencyErrorRegister>) -> Message {
+    Message::Emergency(Emergency::new(node_id, code, register, &[]).unwrap())
+}
+
+#[test]
+fn active_errors_and_reset() {
+    let mut consumer = EmergencyConsumer::new();
+    let temperature_calls = Arc::new(AtomicUsize::new(0));
+    let calls = temperature_calls.clone();
+    consumer.on_error_class(EmergencyErrorClass::Temperature, move |_| {
+        calls.fetch_add(1, SeqCst);
+    });
+
+    consumer.process(&emcy(
+        10,
+        EmergencyErrorCode::DeviceTemperature,
+        vec![EmergencyErrorRegister::Temperature],
+    ));
+    consumer.process(&emcy(
+        10,
+        EmergencyErrorCode::CommunicationCanOverrun,
+        vec![EmergencyErrorRegister::CommunicationError],
+    ));
+    // repeated error does not show up twice
+    consumer.process(&emcy(
+        10,
+        EmergencyErrorCode::DeviceTemperature,
+        vec![EmergencyErrorRegister::Temperature],
+    ));
+    consumer.process(&emcy(11, EmergencyErrorCode::GenericError, vec![]));
+
+    assert_eq!(consumer.active_errors(10).len(), 2);
+    assert_eq!(consumer.nodes_in_error().collect::<Vec<_>>(), vec![10, 11]);
+    assert_eq!(temperature_calls.load(SeqCst), 2);
+
+    consumer.process(&emcy(10, EmergencyErrorCode::ErrorResetOrNoError, vec![]));
+    assert!(!consumer.has_active_errors(10));
+    assert!(consumer.has_active_errors(11));
+    assert_eq!(consumer.history().count(), 5);
+}
+
+#[test]
+fn raw_error_code_round_trip() {
+    let rx = canopeners::Conn::new("vcan0").unwrap();
+    rx.set_read_timeout(std::time::Duration::from_millis(100))
+        .unwrap();
+    let tx = canopeners::Conn::new("vcan0").unwrap();
+
+    let sent = Emergency::with_raw_code(
+        0x22,
+        0xFF42,
+        vec![EmergencyErrorRegister::ManufacturerSpecific],
+        &[1, 2, 3, 4, 5],
+    )
+    .unwrap();
+    tx.send(&Message::Emergency(sent)).unwrap();
+
+    let received = loop {
+        match rx.recv().unwrap() {
+            Message::Emergency(e) if e.node_id() == 0x22 => break e,
+            _ => continue,
+        }
+    };
+    assert_eq!(received.error_code(), &EmergencyErrorCode::DeviceSpecific);
+    assert_eq!(received.error_code_raw(), 0xFF42);
+    assert_eq!(
+        received.error_register(),
+        &[EmergencyErrorRegister::ManufacturerSpecific]
+    );
+    assert_eq!(received.vendor_specific(), &[1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn profile_and_reserved_error_codes_decode() {
+    let rx = canopeners::Conn::new("vcan0").unwrap();
+    rx.set_read_timeout(std::time::Duration::from_millis(100))
+        .unwrap();
+    let tx = canopeners::Conn::new("vcan0").unwrap();
+
+    // CiA 402 codes outside the xx00..=xxFF base ranges, and an unassigned one
+    for (code, expected) in [
+        (0x4310, EmergencyErrorCode::Temperature),
+        (0x5530, EmergencyErrorCode::DeviceHardware),
+        (0x7121, EmergencyErrorCode::AdditionalModules),
+        (0x8611, EmergencyErrorCode::Monitoring),
+        (0x0100, EmergencyErrorCode::Reserved),
+        (0xA000, EmergencyErrorCode::Reserved),
+    ] {
+        let sent = Emergency::with_raw_code(0x23, code, vec![], &[]).unwrap();
+        tx.send(&Message::Emergency(sent)).unwrap();
+
+        let received = loop {
+            match rx.recv().unwrap() {
+                Message::Emergency(e) if e.node_id() == 0x23 => break e,
+                _ => continue,
+            }
+        };
+        assert_eq!(received.error_code(), &expected);
+        assert_eq!(received.error_code_raw(), code);
+    }
+}
+
+#[test]
+fn unassigned_codes_dont_reset() {
+    let mut consumer = EmergencyConsumer::new();
+    consumer.process(&emcy(12, EmergencyErrorCode::DeviceHardware, vec![]));
+    consumer.process(&Message::Emergency(
+        Emergency::with_raw_code(12, 0x0100, vec![], &[]).unwrap(),
+    ));
+    assert!(consumer.has_active_errors(12));
+}
+
+#[test]
+fn too_much_vendor_data_is_an_error() {
+    assert!(Emergency::new(1, EmergencyErrorCode::GenericError, vec![], &[0; 6]).is_err());
+    assert!(Emergency::with_raw_code(1, 0x1000, vec![], &[0; 6]).is_err());
+}
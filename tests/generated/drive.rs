@@ -0,0 +1,66 @@
+// Generated by canopeners::codegen, do not edit.
+// Source: drive.eds
+
+/// Device type (0x1000sub0), Unsigned32, ro
+pub const DEVICE_TYPE: ::canopeners::value::Entry<u32, ::canopeners::value::ReadOnly> = ::canopeners::value::Entry::new(0x1000, 0);
+
+/// Device type (0x1000sub0)
+pub const fn device_type() -> ::canopeners::value::Entry<u32, ::canopeners::value::ReadOnly> {
+    DEVICE_TYPE
+}
+
+/// Manufacturer device name (0x1008sub0), VisibleString, const
+pub const MANUFACTURER_DEVICE_NAME: ::canopeners::value::Entry<String, ::canopeners::value::Const> = ::canopeners::value::Entry::new(0x1008, 0);
+
+/// Manufacturer device name (0x1008sub0)
+pub const fn manufacturer_device_name() -> ::canopeners::value::Entry<String, ::canopeners::value::Const> {
+    MANUFACTURER_DEVICE_NAME
+}
+
+/// Highest sub-index supported (0x1018sub0), Unsigned8, const
+pub const IDENTITY_HIGHEST_SUB_INDEX_SUPPORTED: ::canopeners::value::Entry<u8, ::canopeners::value::Const> = ::canopeners::value::Entry::new(0x1018, 0);
+
+/// Highest sub-index supported (0x1018sub0)
+pub const fn identity_highest_sub_index_supported() -> ::canopeners::value::Entry<u8, ::canopeners::value::Const> {
+    IDENTITY_HIGHEST_SUB_INDEX_SUPPORTED
+}
+
+/// VendorID (0x1018sub1), Unsigned32, ro
+pub const IDENTITY_VENDOR_ID: ::canopeners::value::Entry<u32, ::canopeners::value::ReadOnly> = ::canopeners::value::Entry::new(0x1018, 1);
+
+/// VendorID (0x1018sub1)
+pub const fn identity_vendor_id() -> ::canopeners::value::Entry<u32, ::canopeners::value::ReadOnly> {
+    IDENTITY_VENDOR_ID
+}
+
+/// Type (0x2000sub0), Integer32, rww
+pub const TYPE_: ::canopeners::value::Entry<i32, ::canopeners::value::ReadWrite> = ::canopeners::value::Entry::new(0x2000, 0);
+
+/// Type (0x2000sub0)
+pub const fn type_() -> ::canopeners::value::Entry<i32, ::canopeners::value::ReadWrite> {
+    TYPE_
+}
+
+/// Firmware (0x2001sub0), Domain, rw
+pub const FIRMWARE: ::canopeners::value::Entry<::canopeners::value::Domain, ::canopeners::value::ReadWrite> = ::canopeners::value::Entry::new(0x2001, 0);
+
+/// Firmware (0x2001sub0)
+pub const fn firmware() -> ::canopeners::value::Entry<::canopeners::value::Domain, ::canopeners::value::ReadWrite> {
+    FIRMWARE
+}
+
+/// Controlword (0x6040sub0), Unsigned16, rw
+pub const CONTROLWORD: ::canopeners::value::Entry<u16, ::canopeners::value::ReadWrite> = ::canopeners::value::Entry::new(0x6040, 0);
+
+/// Controlword (0x6040sub0)
+pub const fn controlword() -> ::canopeners::value::Entry<u16, ::canopeners::value::ReadWrite> {
+    CONTROLWORD
+}
+
+/// Modes of operation (0x6060sub0), Integer8, wo
+pub const MODES_OF_OPERATION: ::canopeners::value::Entry<i8, ::canopeners::value::WriteOnly> = ::canopeners::value::Entry::new(0x6060, 0);
+
+/// Modes of operation (0x6060sub0)
+pub const fn modes_of_operation() -> ::canopeners::value::Entry<i8, ::canopeners::value::WriteOnly> {
+    MODES_OF_OPERATION
+}
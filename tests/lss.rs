@@ -0,0 +1,188 @@
+use canopeners::lss::{
+    Lss, LssBitRate, LssBitTiming, LssIdentity, LssMaster, LssMode, LssSlave, LssStorage,
+    LssStoredConfig, UNCONFIGURED_NODE_ID,
+};
+use canopeners::{Conn, Message, Nmt, NmtFunction};
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// LSS messages are broadcast, so tests must not run concurrently
+static BUS: Mutex<()> = Mutex::new(());
+
+const IDENTITY: LssIdentity = LssIdentity {
+    vendor_id: 0x1234,
+    product_code: 0x10,
+    revision_number: 0x20,
+    serial_number: 0xCAFE,
+};
+
+/// keeps what the slave stored
+#[derive(Clone, Default)]
+struct MemoryStorage(Arc<Mutex<Option<LssStoredConfig>>>);
+
+impl LssStorage for MemoryStorage {
+    fn store(&mut self, config: &LssStoredConfig) -> std::io::Result<()> {
+        *self.0.lock().unwrap() = Some(*config);
+        Ok(())
+    }
+}
+
+fn run(mut slave: LssSlave, done: &AtomicBool) -> LssSlave {
+    let conn = Conn::new("vcan0").unwrap();
+    while !done.load(SeqCst) {
+        if let Ok(message) = conn.recv_timeout(Duration::from_millis(5)) {
+            if let Some(response) = slave.process(&message) {
+                conn.send(&response).unwrap();
+            }
+        }
+    }
+    slave
+}
+
+#[test]
+fn master_configures_node_id() {
+    let _bus = BUS.lock().unwrap();
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let slave = s.spawn(|| run(LssSlave::new(IDENTITY, UNCONFIGURED_NODE_ID), &done));
+        let conn = Conn::new("vcan0").unwrap();
+        let lss = LssMaster::new(&conn).with_timeout(Duration::from_millis(200));
+        // give the slave a moment to open its socket
+        std::thread::sleep(Duration::from_millis(20));
+
+        lss.switch_state_selective(&IDENTITY).unwrap();
+        assert_eq!(lss.inquire_identity().unwrap(), IDENTITY);
+        lss.configure_node_id(0x22).unwrap();
+        // out of range
+        assert!(matches!(
+            lss.configure_node_id(0x80),
+            Err(canopeners::CanOpenError::LssConfigurationFailed { error_code: 1, .. })
+        ));
+        // no storage
+        assert!(matches!(
+            lss.store_configuration(),
+            Err(canopeners::CanOpenError::LssConfigurationFailed { error_code: 1, .. })
+        ));
+        lss.switch_state_global(LssMode::Waiting).unwrap();
+        // nobody is in configuration mode anymore
+        assert!(matches!(
+            lss.inquire_node_id(),
+            Err(canopeners::CanOpenError::Timeout(_))
+        ));
+
+        done.store(true, SeqCst);
+        // first node id is used right away
+        assert_eq!(slave.join().unwrap().node_id(), Some(0x22));
+    });
+}
+
+#[test]
+fn fastscan_finds_unconfigured_slaves() {
+    let _bus = BUS.lock().unwrap();
+    let identities = [
+        IDENTITY,
+        LssIdentity {
+            serial_number: 0xCAFF,
+            ..IDENTITY
+        },
+        LssIdentity {
+            vendor_id: 0x4321,
+            product_code: 0xFFFF_FFFF,
+            revision_number: 0,
+            serial_number: 1,
+        },
+    ];
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let slaves: Vec<_> = identities
+            .iter()
+            .map(|identity| s.spawn(|| run(LssSlave::new(*identity, UNCONFIGURED_NODE_ID), &done)))
+            .collect();
+        // already has a node id, must not be found
+        let configured = s.spawn(|| {
+            run(
+                LssSlave::new(
+                    LssIdentity {
+                        serial_number: 7,
+                        ..IDENTITY
+                    },
+                    0x05,
+                ),
+                &done,
+            )
+        });
+        let conn = Conn::new("vcan0").unwrap();
+        let lss = LssMaster::new(&conn).with_timeout(Duration::from_millis(10));
+        std::thread::sleep(Duration::from_millis(20));
+
+        let mut discovered = lss.fastscan(0x30..=0x3F).unwrap();
+        done.store(true, SeqCst);
+
+        discovered.sort_by_key(|d| d.identity);
+        let mut expected = identities.to_vec();
+        expected.sort();
+        assert_eq!(
+            discovered.iter().map(|d| d.identity).collect::<Vec<_>>(),
+            expected
+        );
+        for slave in slaves {
+            let slave = slave.join().unwrap();
+            let found = discovered
+                .iter()
+                .find(|d| d.identity == *slave.identity())
+                .unwrap();
+            assert_eq!(slave.node_id(), Some(found.node_id));
+        }
+        assert_eq!(configured.join().unwrap().node_id(), Some(0x05));
+    });
+}
+
+#[test]
+fn slave_keeps_node_id_until_reset() {
+    let storage = MemoryStorage::default();
+    let mut slave = LssSlave::new(IDENTITY, 0x05).with_storage(storage.clone());
+    slave.handle(&Lss::SwitchStateGlobal(LssMode::Configuration));
+    slave.handle(&Lss::ConfigureNodeId(0x06));
+    slave.handle(&Lss::ConfigureBitTiming {
+        table_selector: 0,
+        table_index: LssBitRate::Kbit250 as u8,
+    });
+    assert!(matches!(
+        slave.handle(&Lss::StoreConfiguration),
+        Some(Lss::StoreConfigurationResponse(result)) if result.is_ok()
+    ));
+    let bit_timing = LssBitTiming {
+        table_selector: 0,
+        table_index: 3,
+    };
+    assert_eq!(
+        *storage.0.lock().unwrap(),
+        Some(LssStoredConfig {
+            node_id: 0x06,
+            bit_timing: Some(bit_timing),
+        })
+    );
+
+    slave.handle(&Lss::ActivateBitTiming {
+        switch_delay_ms: 50,
+    });
+    assert_eq!(
+        slave.take_bit_timing_activation(),
+        Some((bit_timing, Duration::from_millis(50)))
+    );
+
+    // a configured node only switches on reset
+    slave.handle(&Lss::SwitchStateGlobal(LssMode::Waiting));
+    assert_eq!(slave.node_id(), Some(0x05));
+    slave.process(&Message::Nmt(Nmt::new(
+        NmtFunction::ResetCommunication,
+        0x07,
+    )));
+    assert_eq!(slave.node_id(), Some(0x05));
+    slave.process(&Message::Nmt(Nmt::new(
+        NmtFunction::ResetCommunication,
+        0x05,
+    )));
+    assert_eq!(slave.node_id(), Some(0x06));
+}
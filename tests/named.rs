@@ -0,0 +1,207 @@
+mod common;
+
+use canopeners::remote::RemoteNode;
+use canopeners::{eds, named, CanOpenError, Conn};
+use common::SdoServer;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::time::Duration;
+
+const NODE: u8 = 0x49;
+
+const EDS: &str = r#"
+[1017]
+ParameterName=Producer heartbeat time
+ObjectType=0x7
+DataType=0x0006
+AccessType=rw
+DefaultValue=0
+
+[1400]
+ParameterName=RPDO communication parameter 1
+ObjectType=0x9
+SubNumber=2
+
+[1400sub0]
+ParameterName=Highest sub-index supported
+ObjectType=0x7
+DataType=0x0005
+AccessType=const
+DefaultValue=1
+
+[1400sub1]
+ParameterName=COB-ID used by RPDO
+ObjectType=0x7
+DataType=0x0007
+AccessType=rw
+DefaultValue=$NODEID+0x200
+
+[1401]
+ParameterName=RPDO communication parameter 2
+ObjectType=0x9
+SubNumber=2
+
+[1401sub0]
+ParameterName=Highest sub-index supported
+ObjectType=0x7
+DataType=0x0005
+AccessType=const
+DefaultValue=1
+
+[1401sub1]
+ParameterName=COB-ID used by RPDO
+ObjectType=0x7
+DataType=0x0007
+AccessType=rw
+DefaultValue=$NODEID+0x300
+
+[1600]
+ParameterName=Receive PDO mapping
+ObjectType=0x9
+SubNumber=2
+
+[1600sub0]
+ParameterName=Number of mapped objects
+ObjectType=0x7
+DataType=0x0005
+AccessType=rw
+DefaultValue=0
+
+[1600sub1]
+ParameterName=Mapping entry 1
+ObjectType=0x7
+DataType=0x0007
+AccessType=rw
+DefaultValue=0
+
+[1601]
+ParameterName=Receive PDO mapping
+ObjectType=0x9
+SubNumber=3
+
+[1601sub0]
+ParameterName=Number of mapped objects
+ObjectType=0x7
+DataType=0x0005
+AccessType=rw
+DefaultValue=0
+
+[1601sub1]
+ParameterName=Mapping entry 1
+ObjectType=0x7
+DataType=0x0007
+AccessType=rw
+DefaultValue=0
+
+[1601subC]
+ParameterName=Mapping entry 12
+ObjectType=0x7
+DataType=0x0007
+AccessType=rw
+DefaultValue=0
+
+[6040]
+ParameterName=Controlword
+ObjectType=0x7
+DataType=0x0006
+AccessType=rw
+DefaultValue=0
+"#;
+
+#[test]
+fn finds_by_name() {
+    let od = eds::parse(EDS).unwrap();
+    assert_eq!(od.find("Producer heartbeat time").unwrap().index, 0x1017);
+    assert_eq!(od.find(" producer  Heartbeat TIME").unwrap().index, 0x1017);
+    let cob_id = od
+        .find("RPDO communication parameter 2/COB-ID used by RPDO")
+        .unwrap();
+    assert_eq!((cob_id.index, cob_id.sub_index), (0x1401, 1));
+
+    match od.find("COB-ID used by RPDO") {
+        Err(CanOpenError::AmbiguousName { candidates, .. }) => assert_eq!(
+            candidates,
+            [
+                "RPDO communication parameter 1/COB-ID used by RPDO",
+                "RPDO communication parameter 2/COB-ID used by RPDO"
+            ]
+        ),
+        other => panic!("{other:?}"),
+    }
+
+    // same object and sub-index names, only the index tells them apart
+    match od.find("Receive PDO mapping/Mapping entry 1") {
+        Err(CanOpenError::AmbiguousName { candidates, .. }) => {
+            assert_eq!(candidates, ["0x1600sub1", "0x1601sub1"]);
+            for candidate in &candidates {
+                od.find(candidate).unwrap();
+            }
+        }
+        other => panic!("{other:?}"),
+    }
+    let mapping = od.find("0x1601sub1").unwrap();
+    assert_eq!((mapping.index, mapping.sub_index), (0x1601, 1));
+    // sub-indices are decimal, like in error messages
+    let mapping = od.find("Receive PDO mapping/Mapping entry 12").unwrap();
+    assert_eq!(mapping.index_name(), "0x1601sub12");
+    assert_eq!(od.find(&mapping.index_name()).unwrap(), mapping);
+    assert_eq!(od.find("0x6040").unwrap().name, "Controlword");
+    assert!(matches!(
+        od.find("0x6041"),
+        Err(CanOpenError::UnknownName { .. })
+    ));
+
+    let error = od.find("Controlwrod").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "No object called \"Controlwrod\", did you mean Controlword?"
+    );
+    match od.find("Producer heartbeat") {
+        Err(CanOpenError::UnknownName { suggestions, .. }) => {
+            assert_eq!(suggestions, ["Producer heartbeat time"])
+        }
+        other => panic!("{other:?}"),
+    }
+    match od.find("Something else entirely") {
+        Err(CanOpenError::UnknownName { suggestions, .. }) => assert!(suggestions.is_empty()),
+        other => panic!("{other:?}"),
+    }
+}
+
+#[test]
+fn reads_and_writes_by_name() {
+    let od = eds::parse(EDS).unwrap();
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| {
+            SdoServer::new(NODE)
+                .with(0x1017, 0, &100u16.to_le_bytes())
+                .with(0x6040, 0, &[0, 0])
+                .run(&done)
+        });
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+
+        let heartbeat = named::read_by_name(&mut conn, NODE, &od, "Producer heartbeat time");
+        assert_eq!(heartbeat.unwrap().as_str(), "0x64");
+        named::write_by_name(&mut conn, NODE, &od, "Controlword", "0x0F").unwrap();
+        assert!(matches!(
+            named::write_by_name(&mut conn, NODE, &od, "Controlword", "0x10000"),
+            Err(CanOpenError::OverflowError(_))
+        ));
+
+        let mut node = RemoteNode::new(&mut conn, NODE).with_description(od.clone());
+        node.write_by_name("controlword", "6").unwrap();
+        assert_eq!(node.read_named::<u16>("Controlword").unwrap(), 6);
+        assert!(matches!(
+            node.read_named::<u32>("Controlword"),
+            Err(CanOpenError::ParseError(_))
+        ));
+
+        done.store(true, SeqCst);
+        let server = server.join().unwrap();
+        assert_eq!(
+            server.writes,
+            [(0x6040, 0, vec![0x0F, 0]), (0x6040, 0, vec![6, 0])]
+        );
+    });
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use canopeners::enums::{AccessType, DataType, EmergencyErrorCode, ObjectType};
+use canopeners::lss::LssIdentity;
+use canopeners::od::{Object, ObjectDictionary, Variable};
+use canopeners::pdo::{PdoConfig, PdoDirection, PdoMapping, COB_ID_INVALID};
+use canopeners::remote::RemoteNode;
+use canopeners::{CanOpenError, Conn, Emergency, Guard, GuardStatus, Message};
+use common::SdoServer;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::time::Duration;
+
+const NODE: u8 = 0x48;
+
+fn description() -> ObjectDictionary {
+    let mut controlword = Object::new(0x6040, "Controlword", ObjectType::Var);
+    controlword.insert(Variable::new(
+        0x6040,
+        0,
+        "Controlword",
+        DataType::Unsigned16,
+        AccessType::ReadWrite,
+    ));
+    let mut od = ObjectDictionary::new();
+    od.insert(controlword);
+    od
+}
+
+#[test]
+fn remote_node() {
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| {
+            SdoServer::new(NODE)
+                .with(0x1018, 1, &0x1234u32.to_le_bytes())
+                .with(0x1018, 2, &2u32.to_le_bytes())
+                .with(0x1018, 3, &3u32.to_le_bytes())
+                .with(0x1018, 4, &4u32.to_le_bytes())
+                .with(0x6040, 0, &[0, 0])
+                .with(0x1800, 1, &(0x180 + NODE as u32).to_le_bytes())
+                .with(0x1800, 2, &[0xFF])
+                .with(0x1800, 5, &[0, 0])
+                .with(0x1A00, 0, &[1])
+                .with(0x1A00, 1, &0x6041_0010u32.to_le_bytes())
+                .with(0x1A00, 2, &[0; 4])
+                .run(&done)
+        });
+        std::thread::sleep(Duration::from_millis(20));
+        let mut conn = Conn::new("vcan0").unwrap();
+        let mut node = RemoteNode::new(&mut conn, NODE);
+        assert_eq!(
+            node.identity().unwrap(),
+            LssIdentity {
+                vendor_id: 0x1234,
+                product_code: 2,
+                revision_number: 3,
+                serial_number: 4,
+            }
+        );
+        assert!(matches!(
+            node.read_named::<u16>("Controlword"),
+            Err(CanOpenError::NotFound(_))
+        ));
+
+        let mut node = node.with_description(description());
+        node.write_named("Controlword", &0x0Fu16).unwrap();
+        assert_eq!(node.read::<u16>(0x6040, 0).unwrap(), 0x0F);
+
+        let tpdo1 = node.pdo_config(PdoDirection::Tx, 0).unwrap();
+        assert_eq!(tpdo1.inhibit_time, None);
+        assert_eq!(tpdo1.mappings, [PdoMapping::new(0x6041, 0, 16)]);
+        let config = PdoConfig {
+            cob_id: 0x280 + NODE as u32,
+            event_timer: Some(10),
+            mappings: vec![
+                PdoMapping::new(0x6041, 0, 16),
+                PdoMapping::new(0x6064, 0, 32),
+            ],
+            ..tpdo1
+        };
+        node.configure_pdo(PdoDirection::Tx, 0, &config).unwrap();
+        assert_eq!(node.pdo_config(PdoDirection::Tx, 0).unwrap(), config);
+
+        node.process(&Message::Guard(Guard::new(
+            NODE,
+            false,
+            GuardStatus::Operational,
+        )));
+        node.process(&Message::Guard(Guard::new(
+            NODE + 1,
+            false,
+            GuardStatus::Stopped,
+        )));
+        assert_eq!(node.state(), Some(GuardStatus::Operational));
+        assert!(node.last_heartbeat().is_some());
+        assert!(node.last_emergency().is_none());
+        node.process(&Message::Emergency(
+            Emergency::new(NODE, EmergencyErrorCode::GenericError, vec![], &[]).unwrap(),
+        ));
+        assert_eq!(node.last_emergency().unwrap().node_id(), NODE);
+
+        done.store(true, SeqCst);
+        let server = server.join().unwrap();
+        // disabled first, enabled last
+        let cob_ids: Vec<_> = server
+            .writes
+            .iter()
+            .filter(|(index, sub_index, _)| (*index, *sub_index) == (0x1800, 1))
+            .map(|(_, _, data)| u32::from_le_bytes(data[..].try_into().unwrap()))
+            .collect();
+        assert_eq!(cob_ids, [config.cob_id | COB_ID_INVALID, config.cob_id]);
+    });
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use canopeners::lss::LssIdentity;
+use canopeners::scan::Scanner;
+use canopeners::{Conn, Guard, GuardStatus, Message};
+use common::SdoServer;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::time::Duration;
+
+#[test]
+fn finds_nodes_and_identities() {
+    let drive = SdoServer::new(0x30)
+        .with(0x1000, 0, &0x0002_0192u32.to_le_bytes())
+        .with(0x1008, 0, b"drive")
+        .with(0x100A, 0, b"v1.2.3")
+        .with(0x1018, 1, &0x1234u32.to_le_bytes())
+        .with(0x1018, 2, &0x10u32.to_le_bytes())
+        .with(0x1018, 3, &0x0001_0002u32.to_le_bytes())
+        .with(0x1018, 4, &0xCAFEu32.to_le_bytes());
+    // answers, but without a device type
+    let io = SdoServer::new(0x31);
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let drive_server = s.spawn(|| drive.run(&done));
+        let io_server = s.spawn(|| io.run(&done));
+        // no SDO server, only heartbeats
+        s.spawn(|| {
+            let conn = Conn::new("vcan0").unwrap();
+            conn.send(&Message::Guard(Guard::new(0x32, false, GuardStatus::Boot)))
+                .unwrap();
+            while !done.load(SeqCst) {
+                std::thread::sleep(Duration::from_millis(10));
+                conn.send(&Message::Guard(Guard::new(
+                    0x32,
+                    false,
+                    GuardStatus::Operational,
+                )))
+                .unwrap();
+            }
+        });
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+
+        let nodes = Scanner::new()
+            .with_timeout(Duration::from_millis(30))
+            .with_node_ids(0x30..=0x33)
+            .scan(&mut conn)
+            .unwrap();
+        assert_eq!(
+            nodes.iter().map(|n| n.node_id).collect::<Vec<_>>(),
+            vec![0x30, 0x31, 0x32]
+        );
+
+        let drive = &nodes[0];
+        assert!(drive.sdo_responder);
+        assert_eq!(drive.device_type, Some(0x0002_0192));
+        assert_eq!(drive.device_name.as_deref(), Some("drive"));
+        assert_eq!(drive.hardware_version, None);
+        assert_eq!(drive.software_version.as_deref(), Some("v1.2.3"));
+        assert_eq!(
+            drive.lss_identity(),
+            Some(LssIdentity {
+                vendor_id: 0x1234,
+                product_code: 0x10,
+                revision_number: 0x0001_0002,
+                serial_number: 0xCAFE,
+            })
+        );
+
+        let io = &nodes[1];
+        assert!(io.sdo_responder);
+        assert_eq!(io.device_type, None);
+        assert_eq!(io.lss_identity(), None);
+
+        let heartbeat_only = &nodes[2];
+        assert!(!heartbeat_only.sdo_responder);
+        assert_eq!(heartbeat_only.state, Some(GuardStatus::Operational));
+
+        done.store(true, SeqCst);
+        drive_server.join().unwrap();
+        io_server.join().unwrap();
+    });
+}
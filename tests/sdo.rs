@@ -17,7 +17,8 @@ fn sender(done: &AtomicBool) {
         canopeners::enums::EmergencyErrorCode::AmbientTemperature,
         vec![EmergencyErrorRegister::Temperature],
         &[1, 2],
-    );
+    )
+    .unwrap();
     conn.send(&Message::Emergency(emergency)).unwrap();
     let guard = Guard::new(10, false, GuardStatus::Operational);
     conn.send(&Message::Guard(guard)).unwrap();
@@ -0,0 +1,71 @@
+mod common;
+
+use canopeners::enums::AbortCode;
+use canopeners::{CanOpenError, Conn};
+use common::SdoServer;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+
+#[test]
+fn abort_codes_from_u32() {
+    assert_eq!(
+        AbortCode::from(0x0602_0000),
+        AbortCode::ObjectNotInDictionary
+    );
+    assert!(matches!(
+        AbortCode::from(0x0602_0000),
+        AbortCode::ObjectNotInDictionary
+    ));
+    assert_eq!(AbortCode::from(0x0800_1234), AbortCode::Other(0x0800_1234));
+    assert_eq!(u32::from(AbortCode::Other(0x0800_1234)), 0x0800_1234);
+    // the same code, however it was built
+    assert_eq!(
+        AbortCode::Other(0x0602_0000),
+        AbortCode::ObjectNotInDictionary
+    );
+}
+
+#[test]
+fn aborts_report_code_and_object() {
+    let node = 0x4C;
+    let server = SdoServer::new(node)
+        .with_abort(0x2000, 3, AbortCode::Other(0x0800_1234))
+        .with_abort(0x2001, 0, AbortCode::Other(0x0609_0011));
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| server.run(&done));
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        // a manufacturer specific code survives the round trip
+        let error = conn.sdo_read(node, 0x2000, 3).unwrap_err();
+        match &error {
+            CanOpenError::SdoAbortTransfer {
+                index,
+                sub_index,
+                abort_code,
+            } => assert_eq!(
+                (*index, *sub_index, *abort_code),
+                (0x2000, 3, AbortCode::Other(0x0800_1234))
+            ),
+            other => panic!("{other:?}"),
+        }
+        assert_eq!(
+            error.to_string(),
+            "SDO transfer of 0x2000sub3 aborted: unknown or manufacturer specific abort code (0x08001234)"
+        );
+
+        // a known code arrives as its own variant
+        let error = conn.sdo_write(node, 0x2001, 0, &[1]).unwrap_err();
+        assert!(matches!(
+            error,
+            CanOpenError::SdoAbortTransfer {
+                index: 0x2001,
+                sub_index: 0,
+                abort_code: AbortCode::SubIndexDoesNotExist,
+            }
+        ));
+
+        done.store(true, SeqCst);
+        server.join().unwrap();
+    });
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use canopeners::sdo::{SdoChannel, COB_ID_INVALID};
+use canopeners::{CanOpenError, Conn};
+use common::SdoServer;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::time::Duration;
+
+#[test]
+fn transfers_over_custom_cob_ids() {
+    let done = AtomicBool::new(false);
+    let channel = SdoChannel::new(0x43, 0x6F0, 0x6F1);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| {
+            let mut conn = Conn::new("vcan0").unwrap();
+            conn.set_sdo_channel(channel).unwrap();
+            SdoServer::new(0x43)
+                .with(0x2000, 0, &[1, 2])
+                .with(0x2001, 0, b"a longer value")
+                .run_on(conn, &done)
+        });
+        let mut conn = Conn::new("vcan0").unwrap();
+        conn.set_sdo_timeout(Duration::from_millis(200));
+        std::thread::sleep(Duration::from_millis(20));
+
+        // nothing answers on the default COB-IDs
+        assert!(matches!(
+            conn.sdo_read(0x43, 0x2000, 0),
+            Err(CanOpenError::Timeout(_))
+        ));
+
+        conn.set_sdo_channel(channel).unwrap();
+        assert_eq!(conn.sdo_channel(0x43), channel);
+        assert_eq!(&*conn.sdo_read(0x43, 0x2000, 0).unwrap(), &[1, 2]);
+        conn.sdo_write(0x43, 0x2001, 0, b"another long value")
+            .unwrap();
+        assert_eq!(
+            &*conn.sdo_read(0x43, 0x2001, 0).unwrap(),
+            b"another long value"
+        );
+
+        assert_eq!(conn.remove_sdo_channel(0x43), Some(channel));
+        assert_eq!(conn.sdo_channel(0x43), SdoChannel::default_for(0x43));
+        done.store(true, SeqCst);
+        server.join().unwrap();
+    });
+}
+
+#[test]
+fn configures_parameter_records() {
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| {
+            SdoServer::new(0x44)
+                .with_read_only(0x1200, 1, &0x644u32.to_le_bytes())
+                .with_read_only(0x1200, 2, &0x5C4u32.to_le_bytes())
+                .with(0x1281, 1, &COB_ID_INVALID.to_le_bytes())
+                .with(0x1281, 2, &COB_ID_INVALID.to_le_bytes())
+                .with(0x1281, 3, &[0])
+                .run(&done)
+        });
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+
+        let default = SdoChannel::read_server(&mut conn, 0x44, 0).unwrap();
+        assert_eq!(default, SdoChannel::default_for(0x44));
+        assert!(default.configure_server(&mut conn, 0x44, 0).is_err());
+
+        let invalid = SdoChannel::read_client(&mut conn, 0x44, 1).unwrap();
+        assert!(!invalid.is_valid());
+        assert!(conn.set_sdo_channel(invalid).is_err());
+
+        let channel = SdoChannel::new(0x20, 0x6F2, 0x6F3).with_dynamic();
+        channel.configure_client(&mut conn, 0x44, 1).unwrap();
+        let read = SdoChannel::read_client(&mut conn, 0x44, 1).unwrap();
+        assert_eq!(read, channel);
+        assert!(read.is_dynamic());
+
+        done.store(true, SeqCst);
+        let server = server.join().unwrap();
+        let cob_id = channel.cob_id_client_to_server;
+        assert_eq!(
+            server.writes,
+            [
+                (0x1281, 1, (cob_id | COB_ID_INVALID).to_le_bytes().to_vec()),
+                (
+                    0x1281,
+                    2,
+                    channel.cob_id_server_to_client.to_le_bytes().to_vec()
+                ),
+                (0x1281, 1, cob_id.to_le_bytes().to_vec()),
+                (0x1281, 3, vec![0x20]),
+            ]
+        );
+    });
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use canopeners::enums::AbortCode;
+use canopeners::sdo::RetryPolicy;
+use canopeners::{CanOpenError, Conn, Message, ReqRes, Sdo, SdoCmd, SdoCmdUploadSegmentTx};
+use common::SdoServer;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::time::Duration;
+
+const NODE: u8 = 0x46;
+
+/// Ignores the first upload of every object, and gets the toggle bit wrong on the second
+fn flaky_server(done: &AtomicBool) -> Vec<u16> {
+    let conn = Conn::new("vcan0").unwrap();
+    let mut server = SdoServer::new(NODE)
+        .with(0x2000, 0, b"segmented value")
+        .with(0x2001, 0, &[1, 2, 3, 4]);
+    let mut initiated = Vec::new();
+    while !done.load(SeqCst) {
+        let Ok(Message::Sdo(Sdo {
+            node_id: NODE,
+            reqres: ReqRes::Req,
+            command,
+        })) = conn.recv_timeout(Duration::from_millis(5))
+        else {
+            continue;
+        };
+        let attempt = match &command {
+            SdoCmd::InitiateUploadRx(request) => {
+                initiated.push(request.index);
+                initiated.iter().filter(|i| **i == request.index).count()
+            }
+            _ => initiated
+                .iter()
+                .filter(|i| **i == initiated[initiated.len() - 1])
+                .count(),
+        };
+        let response = match server.handle(command) {
+            _ if attempt == 1 => continue,
+            Some(SdoCmd::UploadSegmentTx(segment)) if attempt == 2 => {
+                SdoCmd::UploadSegmentTx(SdoCmdUploadSegmentTx {
+                    toggle: !segment.toggle,
+                    ..segment
+                })
+            }
+            Some(response) => response,
+            None => continue,
+        };
+        conn.send(&Message::Sdo(Sdo {
+            node_id: NODE,
+            reqres: ReqRes::Res,
+            command: response,
+        }))
+        .unwrap();
+    }
+    initiated
+}
+
+#[test]
+fn retries_flaky_transfers() {
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| flaky_server(&done));
+        let mut conn = Conn::new("vcan0").unwrap();
+        conn.set_sdo_timeout(Duration::from_millis(50));
+        std::thread::sleep(Duration::from_millis(20));
+
+        // a single attempt by default
+        assert!(matches!(
+            conn.sdo_read(NODE, 0x2001, 0),
+            Err(CanOpenError::Timeout(_))
+        ));
+
+        let policy =
+            RetryPolicy::new(3).with_backoff(Duration::from_millis(1), Duration::from_millis(5));
+        conn.set_sdo_retry_policy(policy.clone());
+        assert_eq!(&*conn.sdo_read(NODE, 0x2001, 0).unwrap(), &[1, 2, 3, 4]);
+        // timeout, toggle bit, then success
+        assert_eq!(
+            &*conn.sdo_read(NODE, 0x2000, 0).unwrap(),
+            b"segmented value"
+        );
+
+        // not retried
+        match conn.sdo_read(NODE, 0x2002, 0) {
+            Err(CanOpenError::SdoAbortTransfer { abort_code, .. }) => {
+                assert_eq!(abort_code, AbortCode::ObjectNotInDictionary)
+            }
+            other => panic!("{other:?}"),
+        }
+
+        conn.set_sdo_retry_policy(policy.with_retry_on_timeout(false));
+        assert!(matches!(
+            conn.sdo_read(NODE, 0x2003, 0),
+            Err(CanOpenError::Timeout(_))
+        ));
+
+        done.store(true, SeqCst);
+        assert_eq!(
+            server.join().unwrap(),
+            [0x2001, 0x2001, 0x2000, 0x2000, 0x2000, 0x2002, 0x2002, 0x2003]
+        );
+    });
+}
+
+#[test]
+fn backs_off_exponentially() {
+    let policy =
+        RetryPolicy::new(10).with_backoff(Duration::from_millis(10), Duration::from_millis(50));
+    let backoffs: Vec<_> = (1..=5)
+        .map(|attempt| policy.backoff(attempt).as_millis())
+        .collect();
+    assert_eq!(backoffs, [10, 20, 40, 50, 50]);
+    assert_eq!(RetryPolicy::new(0).max_attempts(), 1);
+}
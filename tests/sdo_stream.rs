@@ -0,0 +1,134 @@
+mod common;
+
+use canopeners::sdo::SdoProgress;
+use canopeners::{CanOpenError, Conn, Message};
+use common::SdoServer;
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::time::Duration;
+
+#[test]
+fn streams_with_progress_and_cancel() {
+    let done = AtomicBool::new(false);
+    let image: Vec<u8> = (0..100).collect();
+    std::thread::scope(|s| {
+        let server = s.spawn(|| {
+            SdoServer::new(0x45)
+                .with(0x1F50, 1, &[])
+                .with(0x2000, 0, &[])
+                .run(&done)
+        });
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+
+        let mut progress = Vec::new();
+        conn.sdo_write_from(0x45, 0x1F50, 1, &image[..], 100, |p| {
+            progress.push(p);
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!(progress.len(), 15);
+        assert_eq!(
+            progress[0],
+            SdoProgress {
+                done: 7,
+                total: Some(100)
+            }
+        );
+        assert_eq!(progress[14].done, 100);
+
+        let mut uploaded = Vec::new();
+        let mut progress = Vec::new();
+        let n = conn
+            .sdo_read_into(0x45, 0x1F50, 1, &mut uploaded, |p| {
+                progress.push(p);
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert_eq!((n, &uploaded), (100, &image));
+        assert_eq!(progress.last().unwrap().total, Some(100));
+
+        // cancelled after the second segment
+        let result = conn.sdo_write_from(0x45, 0x2000, 0, &image[..], 100, |p| {
+            if p.done >= 14 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert!(matches!(
+            result,
+            Err(CanOpenError::SdoCancelled {
+                index: 0x2000,
+                sub_index: 0
+            })
+        ));
+        let result = conn.sdo_read_into(0x45, 0x1F50, 1, Vec::new(), |_| ControlFlow::Break(()));
+        assert!(matches!(result, Err(CanOpenError::SdoCancelled { .. })));
+
+        // a reader shorter than announced aborts too
+        let result = conn.sdo_write_from(0x45, 0x2000, 0, &image[..10], 100, |_| {
+            ControlFlow::Continue(())
+        });
+        assert!(matches!(result, Err(CanOpenError::IOError(_))));
+
+        done.store(true, SeqCst);
+        let server = server.join().unwrap();
+        assert_eq!(server.writes, [(0x1F50, 1, image.clone())]);
+    });
+}
+
+/// Takes `delay` for every read
+struct SlowReader<'a> {
+    data: &'a [u8],
+    delay: Duration,
+}
+
+impl std::io::Read for SlowReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        std::thread::sleep(self.delay);
+        self.data.read(buf)
+    }
+}
+
+#[test]
+fn stream_timeout_is_per_segment() {
+    let node = 0x4B;
+    let done = AtomicBool::new(false);
+    let image: Vec<u8> = (0..100).collect();
+    std::thread::scope(|s| {
+        let server = s.spawn(|| SdoServer::new(node).with(0x1F50, 1, &[]).run(&done));
+        let mut conn = Conn::new("vcan0").unwrap();
+        conn.set_sdo_timeout(Duration::from_millis(50));
+        std::thread::sleep(Duration::from_millis(20));
+
+        // 15 segments, the whole stream takes longer than the SDO timeout
+        let start = std::time::Instant::now();
+        let reader = SlowReader {
+            data: &image,
+            delay: Duration::from_millis(5),
+        };
+        conn.sdo_write_from(node, 0x1F50, 1, reader, 100, |_| ControlFlow::Continue(()))
+            .unwrap();
+        assert!(start.elapsed() > Duration::from_millis(50));
+
+        // an expedited download that can't read its data never starts, so isn't aborted
+        let listener = Conn::new("vcan0").unwrap();
+        let result = conn.sdo_write_from(node, 0x1F50, 1, &image[..2], 4, |_| {
+            ControlFlow::Continue(())
+        });
+        assert!(matches!(result, Err(CanOpenError::IOError(_))));
+        let deadline = std::time::Instant::now() + Duration::from_millis(50);
+        while let Some(timeout) = deadline.checked_duration_since(std::time::Instant::now()) {
+            match listener.recv_timeout(timeout) {
+                Ok(Message::Sdo(sdo)) if sdo.node_id == node => panic!("{sdo:?}"),
+                Ok(_) | Err(CanOpenError::Timeout(_)) => {}
+                Err(e) => panic!("{e:?}"),
+            }
+        }
+
+        done.store(true, SeqCst);
+        let server = server.join().unwrap();
+        assert_eq!(server.writes, [(0x1F50, 1, image.clone())]);
+    });
+}
@@ -0,0 +1,68 @@
+use canopeners::enums::AbortCode;
+use canopeners::{
+    CanOpenError, Conn, Guard, GuardStatus, Message, ReqRes, Sdo, SdoCmd, SdoCmdAbortTransfer,
+};
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::time::{Duration, Instant};
+
+#[test]
+fn sdo_times_out_on_busy_bus() {
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        // unrelated traffic, so per-frame read timeouts never fire
+        s.spawn(|| {
+            let conn = Conn::new("vcan0").unwrap();
+            while !done.load(SeqCst) {
+                conn.send(&Message::Guard(Guard::new(
+                    0x41,
+                    false,
+                    GuardStatus::Operational,
+                )))
+                .unwrap();
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        });
+        let abort = s.spawn(|| {
+            let conn = Conn::new("vcan0").unwrap();
+            let deadline = Instant::now() + Duration::from_secs(2);
+            while Instant::now() < deadline {
+                if let Ok(Message::Sdo(Sdo {
+                    node_id: 0x40,
+                    reqres: ReqRes::Req,
+                    command: SdoCmd::AbortTransfer(abort),
+                })) = conn.recv_timeout(Duration::from_millis(100))
+                {
+                    return Some(abort);
+                }
+            }
+            None
+        });
+
+        let mut conn = Conn::new("vcan0").unwrap();
+        conn.set_read_timeout(Duration::from_millis(20)).unwrap();
+        conn.set_sdo_timeout(Duration::from_millis(100));
+        std::thread::sleep(Duration::from_millis(20));
+        let start = Instant::now();
+        assert!(matches!(
+            conn.sdo_read(0x40, 0x1000, 0),
+            Err(CanOpenError::Timeout(100))
+        ));
+        assert!(start.elapsed() < Duration::from_millis(500));
+        // streams time out per segment, on the same timeout
+        assert!(matches!(
+            conn.sdo_read_into(0x4D, 0x1000, 0, Vec::new(), |_| {
+                std::ops::ControlFlow::Continue(())
+            }),
+            Err(CanOpenError::Timeout(100))
+        ));
+        done.store(true, SeqCst);
+
+        let SdoCmdAbortTransfer {
+            index,
+            sub_index,
+            abort_code,
+        } = abort.join().unwrap().unwrap();
+        assert_eq!((index, sub_index), (0x1000, 0));
+        assert_eq!(abort_code, AbortCode::SdoProtocolTimedOut);
+    });
+}
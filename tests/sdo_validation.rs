@@ -0,0 +1,104 @@
+use canopeners::enums::AbortCode;
+use canopeners::{
+    CanOpenError, Conn, Message, ReqRes, Sdo, SdoCmd, SdoCmdInitiateDownloadTx,
+    SdoCmdInitiatePayload, SdoCmdInitiateUploadRx, SdoCmdInitiateUploadTx, SdoCmdUploadSegmentTx,
+};
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::time::Duration;
+
+const NODE: u8 = 0x42;
+
+/// Answers uploads wrong in a different way per index, returns the aborts it got
+fn broken_server(done: &AtomicBool) -> Vec<(u16, AbortCode)> {
+    let conn = Conn::new("vcan0").unwrap();
+    let mut aborts = Vec::new();
+    let mut index = 0;
+    let respond = |command| {
+        conn.send(&Message::Sdo(Sdo {
+            node_id: NODE,
+            reqres: ReqRes::Res,
+            command,
+        }))
+        .unwrap()
+    };
+    while !done.load(SeqCst) {
+        let Ok(Message::Sdo(Sdo {
+            node_id: NODE,
+            reqres: ReqRes::Req,
+            command,
+        })) = conn.recv_timeout(Duration::from_millis(5))
+        else {
+            continue;
+        };
+        match command {
+            SdoCmd::InitiateUploadRx(SdoCmdInitiateUploadRx {
+                index: i,
+                sub_index,
+            }) => {
+                index = i;
+                let (index, payload) = match i {
+                    0x2000 => (i, SdoCmdInitiatePayload::Segmented(Some(10))),
+                    0x2001 => (i, SdoCmdInitiatePayload::Segmented(Some(3))),
+                    0x2002 => (0x2003, SdoCmdInitiatePayload::Expedited([1].into())),
+                    _ => {
+                        respond(SdoCmd::InitiateDownloadTx(SdoCmdInitiateDownloadTx {
+                            index: i,
+                            sub_index,
+                        }));
+                        continue;
+                    }
+                };
+                respond(SdoCmd::InitiateUploadTx(SdoCmdInitiateUploadTx {
+                    index,
+                    sub_index,
+                    payload,
+                }));
+            }
+            SdoCmd::UploadSegmentRx(request) => {
+                respond(SdoCmd::UploadSegmentTx(SdoCmdUploadSegmentTx {
+                    // 0x2000 doesn't toggle, 0x2001 sends more than announced
+                    toggle: if index == 0x2000 {
+                        !request.toggle
+                    } else {
+                        request.toggle
+                    },
+                    data: [0; 7].into(),
+                    last: true,
+                }))
+            }
+            SdoCmd::AbortTransfer(abort) => aborts.push((abort.index, abort.abort_code)),
+            _ => {}
+        }
+    }
+    aborts
+}
+
+#[test]
+fn aborts_broken_transfers() {
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| broken_server(&done));
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+
+        let expected = [
+            (0x2000, AbortCode::ToggleBitNotAlternated),
+            (0x2001, AbortCode::DataTypeMismatchLengthMismatch),
+            (0x2002, AbortCode::GeneralError),
+            (0x2004, AbortCode::InvalidClientServerCommandSpecifier),
+        ];
+        for (index, abort_code) in expected {
+            match conn.sdo_read(NODE, index, 0) {
+                Err(CanOpenError::SdoAbortTransfer {
+                    index: i,
+                    abort_code: code,
+                    ..
+                }) => assert_eq!((i, code), (index, abort_code)),
+                other => panic!("{index:#06x}: {other:?}"),
+            }
+        }
+        std::thread::sleep(Duration::from_millis(20));
+        done.store(true, SeqCst);
+        assert_eq!(server.join().unwrap(), expected);
+    });
+}
@@ -0,0 +1,133 @@
+use canopeners::sync::{SyncConfig, SyncProducer};
+use canopeners::{CanOpenError, Conn, Message};
+
+#[test]
+fn producer_counter_wraps() {
+    let rx = Conn::new("vcan0").unwrap();
+    rx.set_read_timeout(std::time::Duration::from_millis(100))
+        .unwrap();
+    let producer =
+        SyncProducer::start(Conn::new("vcan0").unwrap(), SyncConfig::new(0x80, 2000, 3)).unwrap();
+
+    let mut counters = Vec::new();
+    while counters.len() < 7 {
+        if let Message::Sync(sync) = rx.recv().unwrap() {
+            counters.push(sync.counter.unwrap());
+        }
+    }
+    producer.stop().unwrap();
+
+    assert_eq!(counters, vec![1, 2, 3, 1, 2, 3, 1]);
+}
+
+#[test]
+fn invalid_overflow_rejected() {
+    let conn = Conn::new("vcan0").unwrap();
+    assert!(SyncProducer::start(conn, SyncConfig::new(0x80, 1000, 1)).is_err());
+}
+
+#[test]
+fn cycles_group_pdos() {
+    use canopeners::sync::SyncCycleConsumer;
+    use canopeners::{Pdo, Sync};
+    use std::time::{Duration, Instant};
+
+    let mut consumer = SyncCycleConsumer::new()
+        .with_window_length_us(1000)
+        .with_expected_pdos([0x20A, 0x20B]);
+    let start = Instant::now();
+    let pdo = |node_id| Message::Pdo(Pdo::new(node_id, 1, &[1, 2]).unwrap());
+
+    // before the first SYNC, dropped
+    assert!(consumer.process(&pdo(10), start).is_none());
+    assert!(consumer
+        .process(&Message::Sync(Sync::new(Some(1))), start)
+        .is_none());
+    assert!(consumer
+        .process(&pdo(10), start + Duration::from_micros(500))
+        .is_none());
+    assert!(consumer
+        .process(&pdo(12), start + Duration::from_micros(1500))
+        .is_none());
+    let cycle = consumer
+        .process(
+            &Message::Sync(Sync::new(Some(2))),
+            start + Duration::from_millis(2),
+        )
+        .unwrap();
+
+    assert_eq!(cycle.counter, Some(1));
+    assert_eq!(cycle.pdos.len(), 2);
+    assert_eq!(
+        cycle
+            .late_pdos()
+            .map(|p| p.pdo.node_id())
+            .collect::<Vec<_>>(),
+        vec![12]
+    );
+    assert_eq!(cycle.missing, vec![0x20B]);
+}
+
+#[test]
+fn sync_on_configured_cob_id() {
+    use canopeners::Sync;
+
+    let mut rx = Conn::new("vcan0").unwrap();
+    rx.set_read_timeout(std::time::Duration::from_millis(100))
+        .unwrap();
+    rx.set_sync_cob_id(0x1F0);
+    let tx = Conn::new("vcan0").unwrap();
+
+    // sent first, but 0x080 isn't the SYNC COB-ID anymore
+    tx.send(&Message::Sync(Sync::new(Some(6)))).unwrap();
+    tx.send(&Message::Sync(Sync::with_cob_id(0x1F0, Some(7))))
+        .unwrap();
+    let sync = loop {
+        match rx.recv() {
+            Ok(Message::Sync(sync)) => break sync,
+            Err(CanOpenError::Timeout(_) | CanOpenError::IOError(_)) => panic!("no SYNC"),
+            _ => continue,
+        }
+    };
+    assert_eq!(sync.cob_id(), 0x1F0);
+    assert_eq!(sync.counter, Some(7));
+}
+
+#[test]
+fn cycles_track_received_tpdos() {
+    use canopeners::sync::SyncCycleConsumer;
+    use canopeners::Sync;
+    use socketcan::{CanFrame, CanSocket, EmbeddedFrame, Socket, StandardId};
+    use std::time::Instant;
+
+    let rx = Conn::new("vcan0").unwrap();
+    rx.set_read_timeout(std::time::Duration::from_millis(100))
+        .unwrap();
+    let tx = CanSocket::open("vcan0").unwrap();
+
+    let mut consumer = SyncCycleConsumer::new().with_expected_pdos([0x181, 0x281, 0x381]);
+    let start = Instant::now();
+    consumer.process(&Message::Sync(Sync::new(None)), start);
+
+    // TPDO1 and TPDO2 of node 1, as a node sends them
+    for id in [0x181, 0x281] {
+        let frame = CanFrame::new(StandardId::new(id).unwrap(), &[id as u8, 0]).unwrap();
+        tx.write_frame(&frame).unwrap();
+    }
+    let mut received = 0;
+    while received < 2 {
+        if let Message::Pdo(pdo) = rx.recv().unwrap() {
+            if pdo.node_id() == 1 {
+                consumer.process(&Message::Pdo(pdo), start);
+                received += 1;
+            }
+        }
+    }
+
+    let cycle = consumer
+        .process(&Message::Sync(Sync::new(None)), Instant::now())
+        .unwrap();
+    assert_eq!(cycle.pdo(0x181).unwrap().data(), &[0x81, 0]);
+    assert_eq!(cycle.pdo(0x281).unwrap().data(), &[0x81, 0]);
+    assert_eq!(cycle.missing, vec![0x381]);
+}
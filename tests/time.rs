@@ -0,0 +1,30 @@
+use canopeners::time::{TimeConsumer, TimeProducer};
+use canopeners::{Conn, TimeOfDay};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[test]
+fn time_of_day_conversions() {
+    // 1984-01-02 00:00:01.5 UTC
+    let t = UNIX_EPOCH + Duration::from_secs(441_763_200 + 86_400 + 1) + Duration::from_millis(500);
+    let tod = TimeOfDay::try_from(t).unwrap();
+    assert_eq!(tod, TimeOfDay::new(1500, 1));
+    assert_eq!(SystemTime::from(tod), t);
+    assert!(TimeOfDay::try_from(UNIX_EPOCH).is_err());
+}
+
+#[test]
+fn producer_and_consumer() {
+    let rx = Conn::new("vcan0").unwrap();
+    rx.set_read_timeout(Duration::from_millis(500)).unwrap();
+    let producer =
+        TimeProducer::start(Conn::new("vcan0").unwrap(), Duration::from_millis(5)).unwrap();
+
+    let mut consumer = TimeConsumer::new();
+    while consumer.offset().is_none() {
+        consumer.process(&rx.recv().unwrap());
+    }
+    producer.stop().unwrap();
+
+    // same clock on both ends, just the 1ms resolution and scheduling noise
+    assert!(consumer.offset().unwrap().offset_ms.abs() < 100);
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use canopeners::enums::DataType;
+use canopeners::value::{Domain, SdoValue, TimeDifference, UnicodeString, I24, I48, U24, U56};
+use canopeners::{CanOpenError, Conn, TimeOfDay};
+use common::SdoServer;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::time::Duration;
+
+fn round_trip<T: SdoValue + PartialEq + std::fmt::Debug>(value: T, encoded: &[u8]) {
+    assert_eq!(value.encode(), encoded);
+    assert_eq!(T::decode(encoded).unwrap(), value);
+    if let Some(size) = T::DATA_TYPE.size() {
+        assert_eq!(size, encoded.len());
+    }
+}
+
+#[test]
+fn encodes_basic_types() {
+    round_trip(true, &[1]);
+    round_trip(-2i16, &[0xFE, 0xFF]);
+    round_trip(0x1234_5678u32, &[0x78, 0x56, 0x34, 0x12]);
+    round_trip(1.5f32, &1.5f32.to_le_bytes());
+    round_trip(I24::new(-2).unwrap(), &[0xFE, 0xFF, 0xFF]);
+    round_trip(
+        I48::new(0x7FFF_FFFF_FFFF).unwrap(),
+        &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F],
+    );
+    round_trip(U24::new(0xABCDEF).unwrap(), &[0xEF, 0xCD, 0xAB]);
+    round_trip(U56::new(1).unwrap(), &[1, 0, 0, 0, 0, 0, 0]);
+    round_trip("abc".to_owned(), b"abc");
+    round_trip(vec![1u8, 2, 3], &[1, 2, 3]);
+    round_trip(UnicodeString("hé".to_owned()), &[b'h', 0, 0xE9, 0]);
+    round_trip(Domain(vec![0xDE, 0xAD]), &[0xDE, 0xAD]);
+    round_trip(TimeOfDay::new(1000, 2), &[0xE8, 0x03, 0, 0, 2, 0]);
+    round_trip(TimeDifference::new(1, 1), &[1, 0, 0, 0, 1, 0]);
+
+    assert!(matches!(
+        U24::new(0x0100_0000),
+        Err(CanOpenError::OverflowError(_))
+    ));
+    assert!(matches!(
+        I24::new(0x0080_0000),
+        Err(CanOpenError::OverflowError(_))
+    ));
+    assert!(matches!(
+        u16::decode(&[1]),
+        Err(CanOpenError::ParseError(_))
+    ));
+    assert_eq!(I24::DATA_TYPE, DataType::Integer24);
+    assert_eq!(
+        Duration::from(TimeDifference::try_from(Duration::from_secs(90_000)).unwrap()),
+        Duration::from_secs(90_000)
+    );
+}
+
+#[test]
+fn typed_sdo_checks_length() {
+    let node = 0x17;
+    let server = SdoServer::new(node)
+        .with(0x2000, 0, &[0, 0, 0])
+        .with(0x2001, 0, &[1, 2]);
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let server = s.spawn(|| server.run(&done));
+        let mut conn = Conn::new("vcan0").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+
+        let value = U24::new(0x12_3456).unwrap();
+        conn.sdo_write_typed(node, 0x2000, 0, &value).unwrap();
+        assert_eq!(conn.sdo_read_typed::<U24>(node, 0x2000, 0).unwrap(), value);
+        assert!(matches!(
+            conn.sdo_read_typed::<u32>(node, 0x2001, 0),
+            Err(CanOpenError::ParseError(_))
+        ));
+        assert_eq!(conn.sdo_read_typed::<u16>(node, 0x2001, 0).unwrap(), 0x0201);
+
+        done.store(true, SeqCst);
+        server.join().unwrap();
+    });
+}
@@ -0,0 +1,137 @@
+use canopeners::enums::{AccessType, DataType, ObjectType};
+use canopeners::{eds, xdd, CanOpenError};
+
+const XDC: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<ISO15745ProfileContainer xmlns="http://www.canopen.org/xml/1.1">
+  <ISO15745Profile>
+    <ProfileHeader>
+      <ProfileName>Widget device profile</ProfileName>
+    </ProfileHeader>
+    <ProfileBody fileName="widget.xdc" fileCreator="canopeners" fileCreationDate="2024-01-01" fileVersion="1">
+      <DeviceIdentity>
+        <vendorName>ACME</vendorName>
+        <vendorID>0x00001234</vendorID>
+        <productName>Widget</productName>
+        <productID>0x10</productID>
+      </DeviceIdentity>
+      <ApplicationProcess>
+        <parameterList>
+          <parameter uniqueID="UID_GAIN" access="readWriteInput">
+            <label lang="en">Gain</label>
+            <INT/>
+            <defaultValue value="10"/>
+            <actualValue value="-3"/>
+            <allowedValues>
+              <range>
+                <minValue value="-100"/>
+                <maxValue value="100"/>
+              </range>
+            </allowedValues>
+          </parameter>
+        </parameterList>
+      </ApplicationProcess>
+    </ProfileBody>
+  </ISO15745Profile>
+  <ISO15745Profile>
+    <ProfileHeader>
+      <ProfileName>Widget communication profile</ProfileName>
+    </ProfileHeader>
+    <ProfileBody fileName="widget.xdc">
+      <ApplicationLayers>
+        <CANopenObjectList>
+          <CANopenObject index="1000" name="Device type" objectType="7" dataType="0007" accessType="ro" defaultValue="0x00020192" PDOmapping="no"/>
+          <CANopenObject index="1018" name="Identity" objectType="9" subNumber="2">
+            <CANopenSubObject subIndex="00" name="Highest sub-index supported" objectType="7" dataType="0005" accessType="const" defaultValue="3"/>
+            <CANopenSubObject subIndex="03" name="Revision number" objectType="7" dataType="0007" accessType="ro" defaultValue="0x00010002"/>
+          </CANopenObject>
+          <CANopenObject index="1800" name="TPDO1 communication parameter" objectType="9" subNumber="1">
+            <CANopenSubObject subIndex="01" name="COB-ID" objectType="7" dataType="0007" accessType="rw" defaultValue="$NODEID+0x180"/>
+          </CANopenObject>
+          <CANopenObject index="2000" name="Gain" objectType="7" PDOmapping="RPDO" uniqueIDRef="UID_GAIN"/>
+        </CANopenObjectList>
+      </ApplicationLayers>
+      <TransportLayers>
+        <PhysicalLayer>
+          <baudRate defaultValue="250 Kbps">
+            <supportedBaudRate value="500 Kbps"/>
+            <supportedBaudRate value="250 Kbps"/>
+            <supportedBaudRate value="auto-baudRate"/>
+          </baudRate>
+        </PhysicalLayer>
+      </TransportLayers>
+      <NetworkManagement>
+        <CANopenGeneralFeatures granularity="8" nrOfRxPDO="0" nrOfTxPDO="1" bootUpSlave="true" layerSettingServiceSlave="true"/>
+        <deviceCommissioning nodeID="5" nodeName="widget" actualBaudRate="250 Kbps" networkNumber="1" CANopenManager="false"/>
+      </NetworkManagement>
+    </ProfileBody>
+  </ISO15745Profile>
+</ISO15745ProfileContainer>
+"#;
+
+#[test]
+fn parses_xdc() {
+    let od = xdd::parse(XDC).unwrap();
+    assert_eq!(od.file_info.file_name, "widget.xdc");
+    assert_eq!(od.file_info.created_by, "canopeners");
+    assert_eq!(od.file_info.description, "Widget device profile");
+    assert_eq!(od.device_info.vendor_name, "ACME");
+    assert_eq!(od.device_info.vendor_number, Some(0x1234));
+    assert_eq!(od.device_info.revision_number, Some(0x0001_0002));
+    assert_eq!(od.device_info.baud_rates, vec![250, 500]);
+    assert_eq!(od.device_info.nr_of_tx_pdo, 1);
+    assert!(od.device_info.lss_supported);
+    assert_eq!(od.device_commissioning.as_ref().unwrap().node_id, 5);
+    assert_eq!(od.device_commissioning.as_ref().unwrap().baudrate, 250);
+
+    let device_type = od.variable(0x1000, 0).unwrap();
+    assert_eq!(device_type.data_type, DataType::Unsigned32);
+    assert_eq!(device_type.access_type, AccessType::ReadOnly);
+    assert_eq!(od.get(0x1018).unwrap().object_type, ObjectType::Record);
+    let cob_id = od.variable(0x1800, 1).unwrap();
+    assert_eq!(
+        cob_id
+            .default_value
+            .as_ref()
+            .unwrap()
+            .to_integer(5)
+            .unwrap(),
+        0x185
+    );
+
+    // inherited from the ApplicationProcess parameter
+    let gain = od.variable(0x2000, 0).unwrap();
+    assert_eq!(gain.data_type, DataType::Integer16);
+    assert_eq!(gain.access_type, AccessType::ReadWriteWrite);
+    assert!(gain.pdo_mapping);
+    assert_eq!(gain.default_value.as_ref().unwrap().as_str(), "10");
+    assert_eq!(gain.parameter_value.as_ref().unwrap().as_str(), "-3");
+    assert_eq!(gain.low_limit.as_ref().unwrap().as_str(), "-100");
+}
+
+#[test]
+fn converts_to_eds() {
+    let mut od = xdd::parse(XDC).unwrap();
+    let converted = eds::parse(&eds::write(&od)).unwrap();
+    // XDDs have no EDS version, the exporter fills it in
+    assert_eq!(converted.file_info.eds_version, "4.0");
+    od.file_info.eds_version = "4.0".to_owned();
+    assert_eq!(converted, od);
+}
+
+#[test]
+fn errors_point_at_the_line() {
+    let bad = XDC.replace(r#"accessType="rw""#, r#"accessType="sometimes""#);
+    let line = XDC
+        .lines()
+        .position(|l| l.contains(r#"accessType="rw""#))
+        .unwrap()
+        + 1;
+    assert!(matches!(
+        xdd::parse(&bad),
+        Err(CanOpenError::XddError { line: l, .. }) if l == line
+    ));
+    assert!(matches!(
+        xdd::parse("<ISO15745ProfileContainer>"),
+        Err(CanOpenError::XddError { .. })
+    ));
+}